@@ -1,4 +1,4 @@
-use nogine::{graphics::{Graphics, BlendingMode}, window::{WindowCfg, WindowMode}, color::{Color4, Color}, math::vec2, unwrap_res};
+use nogine::{graphics::{Graphics, BlendingMode, BlendFactor, BlendOp}, window::{WindowCfg, WindowMode}, color::{Color4, Color}, math::vec2, unwrap_res};
 
 fn main() {
     // Create Window
@@ -13,16 +13,35 @@ fn main() {
         Graphics::set_cam(vec2::ZERO, vec2(1.5 * window.aspect_ratio(), 1.5));
 
         // Background
-        Graphics::draw_rect(vec2(-2.0, -0.5), vec2(4.0, 1.0), Color4::GRAY);
+        Graphics::draw_rect(vec2(-2.75, -0.25), vec2(5.5, 0.5), Color4::GRAY);
 
         // Default is Alpha mix
-        Graphics::draw_rect(vec2(-1.75, -1.0), vec2(1.0, 2.0), Color4::RED);
+        Graphics::draw_rect(vec2(-2.5, 0.25), vec2(1.0, 1.0), Color4::RED);
 
         Graphics::set_blending_mode(BlendingMode::Additive);
-        Graphics::draw_rect(vec2(-0.5, -1.0), vec2(1.0, 2.0), Color4::GREEN);
+        Graphics::draw_rect(vec2(-1.25, 0.25), vec2(1.0, 1.0), Color4::GREEN);
 
         Graphics::set_blending_mode(BlendingMode::Multiplicative);
-        Graphics::draw_rect(vec2(0.75, -1.0), vec2(1.0, 2.0), Color4::BLUE);
+        Graphics::draw_rect(vec2(0.0, 0.25), vec2(1.0, 1.0), Color4::BLUE);
+
+        Graphics::set_blending_mode(BlendingMode::Screen);
+        Graphics::draw_rect(vec2(1.25, 0.25), vec2(1.0, 1.0), Color4::RED);
+
+        Graphics::set_blending_mode(BlendingMode::Subtract);
+        Graphics::draw_rect(vec2(-2.5, -1.25), vec2(1.0, 1.0), Color4::GREEN);
+
+        Graphics::set_blending_mode(BlendingMode::Max);
+        Graphics::draw_rect(vec2(-1.25, -1.25), vec2(1.0, 1.0), Color4::BLUE);
+
+        Graphics::set_blending_mode(BlendingMode::Overlay);
+        Graphics::draw_rect(vec2(0.0, -1.25), vec2(1.0, 1.0), Color4::YELLOW);
+
+        // Custom blending, inverts the background
+        Graphics::set_blending_mode(BlendingMode::Custom {
+            src: BlendFactor::OneMinusDstColor, dst: BlendFactor::Zero, op: BlendOp::Add,
+            alpha_src: BlendFactor::Zero, alpha_dst: BlendFactor::One, alpha_op: BlendOp::Add
+        });
+        Graphics::draw_rect(vec2(1.25, -1.25), vec2(1.0, 1.0), Color4::WHITE);
 
         // Restore AlphaMix once finished
         Graphics::set_blending_mode(BlendingMode::AlphaMix);
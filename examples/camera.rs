@@ -0,0 +1,50 @@
+use nogine::{color::{Color, Color4}, graphics::{camera::Camera2D, Graphics}, input::{Input, KeyInput, MouseInput}, math::{vec2, Rect}, unwrap_res, window::{WindowCfg, WindowMode}};
+
+fn main() {
+    // Create Window
+    let mut window = unwrap_res!(WindowCfg::default().res((1280, 720)).title("Camera Example").mode(WindowMode::Windowed).init());
+
+    // Setup camera
+    let mut cam = Camera2D::new(vec2::ZERO, vec2(5.0 * window.aspect_ratio(), 5.0));
+    cam.dead_zone = vec2(1.0, 0.5);
+    cam.smoothing = 0.15;
+    cam.bounds = Some(Rect { start: vec2(-20.0, -12.0), end: vec2(20.0, 12.0) });
+
+    let mut player = vec2::ZERO;
+    let mut marker = vec2::ZERO;
+    while window.is_running() {
+        window.pre_tick(None);
+
+        // Move player
+        let dir = vec2(Input::axis(KeyInput::A, KeyInput::D) as f32, Input::axis(KeyInput::S, KeyInput::W) as f32);
+        player = player + dir * (8.0 * window.ts());
+
+        // Update camera
+        cam.rot += Input::axis(KeyInput::Q, KeyInput::E) as f32 * window.ts();
+        cam.zoom = (cam.zoom * (1.0 + Input::axis(KeyInput::Z, KeyInput::X) as f32 * window.ts())).clamp(0.25, 4.0);
+        if Input::key_pressed(KeyInput::Space) {
+            cam.add_trauma(0.5);
+        }
+
+        cam.follow(player);
+        cam.update(window.ts());
+        cam.apply();
+
+        if Input::mouse_pressed(MouseInput::Left) {
+            marker = cam.screen_to_world(Input::mouse_pos(), window.get_size());
+        }
+
+        // Draw level
+        Graphics::draw_rect(vec2(-20.0, -12.0), vec2(40.0, 24.0), Color4(0.15, 0.15, 0.2, 1.0));
+        for x in -4..4 {
+            for y in -2..3 {
+                Graphics::draw_circle(vec2(x as f32 * 5.0 + 2.5, y as f32 * 5.0), 0.5, Color4::GRAY);
+            }
+        }
+
+        Graphics::draw_rect(player - vec2(0.25, 0.25), vec2(0.5, 0.5), Color4::CYAN);
+        Graphics::draw_circle(marker, 0.2, Color4::RED);
+
+        window.post_tick();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,56 @@
+use nogine::{graphics::{Graphics, pipeline::{ColorFormat, RenderPipeline, RenderTexture, SceneRenderData, ToneMapping, DEFAULT_RENDER_TARGET}, RenderStats, texture::TextureFiltering, BlendingMode}, input::{Input, KeyInput}, window::{WindowCfg, WindowMode}, color::Color4, math::vec2, unwrap_res};
+
+struct HdrPipeline {
+    mapping: ToneMapping,
+    exposure: f32,
+}
+
+impl RenderPipeline for HdrPipeline {
+    fn render(&self, screen_rt: &mut RenderTexture, scene_data: &SceneRenderData, _ui_data: Option<&SceneRenderData>, stats: &mut RenderStats) {
+        // Render scene to a float texture, so overlapping lights go over 1.0
+        let mut hdr_rt = RenderTexture::builder(screen_rt.res()).filtering(TextureFiltering::Linear).color(ColorFormat::RGBA16F).build();
+        hdr_rt.clear(scene_data.clear_col());
+        hdr_rt.render_scene(scene_data, DEFAULT_RENDER_TARGET, stats);
+
+        // Map it back to the screen
+        screen_rt.tonemap(&hdr_rt, self.mapping, self.exposure, stats);
+    }
+}
+
+fn main() {
+    // Create Window
+    let mut window = unwrap_res!(WindowCfg::default().res((1280, 720)).title("HDR Example. Up/Down: Exposure, Space: Tone mapping").mode(WindowMode::Windowed).init());
+
+    // Setup graphics
+    Graphics::set_clear_col(Color4(0.05, 0.05, 0.1, 1.0));
+
+    let mut pipeline = HdrPipeline { mapping: ToneMapping::Aces, exposure: 1.0 };
+    
+    while window.is_running() {
+        window.pre_tick(Some(&pipeline));
+
+        if Input::key(KeyInput::Up) {
+            pipeline.exposure *= 1.02;
+        }
+        if Input::key(KeyInput::Down) {
+            pipeline.exposure /= 1.02;
+        }
+        if Input::key_pressed(KeyInput::Space) {
+            pipeline.mapping = match pipeline.mapping {
+                ToneMapping::Reinhard => ToneMapping::Aces,
+                ToneMapping::Aces => ToneMapping::Reinhard,
+            };
+        }
+
+        Graphics::set_cam(vec2::ZERO, vec2(1.5 * window.aspect_ratio(), 1.5));
+
+        // Overlapping lights
+        Graphics::set_blending_mode(BlendingMode::Additive);
+        Graphics::draw_circle(vec2(-0.4, 0.0), 0.7, Color4(2.0, 0.6, 0.2, 1.0));
+        Graphics::draw_circle(vec2(0.4, 0.0), 0.7, Color4(0.2, 0.6, 2.0, 1.0));
+        Graphics::draw_circle(vec2(0.0, 0.5), 0.7, Color4(0.4, 2.0, 0.4, 1.0));
+        Graphics::set_blending_mode(BlendingMode::AlphaMix);
+        
+        window.post_tick();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,25 @@
+use nogine::{graphics::Graphics, window::WindowCfg, color::{Color4, Color}, math::vec2, unwrap_res, log_info};
+
+const FRAME_COUNT: u32 = 60;
+
+fn main() {
+    // Create a hidden window that renders offscreen
+    let mut window = unwrap_res!(WindowCfg::default().res((1280, 720)).title("Headless Example").headless(true).init());
+
+    for _ in 0..FRAME_COUNT {
+        let stats = window.pre_tick(None);
+        
+        Graphics::set_cam(vec2::ZERO, vec2(1.5 * window.aspect_ratio(), 1.5));
+
+        Graphics::draw_rect(vec2(-1.55, -0.5), vec2::ONE, Color4::CYAN);
+        Graphics::draw_circle(vec2::ZERO, 0.5, Color4::YELLOW);
+        Graphics::draw_polygon(vec2(1.0, 0.0), 0.5, 0.0, 6, Color::PINK);
+        
+        window.post_tick();
+        log_info!("Draw calls: {}", stats.draw_calls());
+    }
+
+    // Save the last rendered frame
+    let target = window.headless_target().unwrap();
+    unwrap_res!(target.save_png("headless.png"));
+}
@@ -0,0 +1,24 @@
+use nogine::{graphics::{Graphics, shader::Shader, Mode, uniforms::Uniform, material::Material}, window::{WindowCfg, WindowMode}, color::{Color4, Color}, math::vec2, unwrap_res};
+
+fn main() {
+    // Create Window
+    let mut window = unwrap_res!(WindowCfg::default().res((1280, 720)).title("Hot Reload Example").mode(WindowMode::Windowed).init());
+
+    // Shaders loaded from files are recompiled when the files change
+    Shader::set_hot_reload(true);
+    let shader = unwrap_res!(Shader::from_files("examples/res/hot_reload.vert", "examples/res/hot_reload.frag"));
+    let material = Material::new(&shader, &[(b"tint\0", Uniform::Float3(1.0, 0.0, 1.0))]);
+
+    // Setup graphics
+    Graphics::set_material(Some(material), Mode::Rect);
+
+    while window.is_running() {
+        window.pre_tick(None);
+
+        Graphics::set_cam(vec2::ZERO, vec2(1.5 * window.aspect_ratio(), 1.5));
+        
+        Graphics::draw_rect(vec2(-0.5, -0.5), vec2::ONE, Color4::CYAN);
+        
+        window.post_tick();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,28 @@
+use nogine::{graphics::Graphics, window::{WindowCfg, WindowMode}, color::{Color4, Color}, math::vec2, unwrap_res};
+
+fn main() {
+    // Create Window
+    let mut window = unwrap_res!(WindowCfg::default().res((1280, 720)).title("Masking Example").mode(WindowMode::Windowed).init());
+
+    let mut time: f32 = 0.0;
+    while window.is_running() {
+        window.pre_tick(None);
+        
+        Graphics::set_cam(vec2::ZERO, vec2(1.5 * window.aspect_ratio(), 1.5));
+
+        // Write the mask
+        Graphics::begin_mask();
+        Graphics::draw_circle(vec2(time.sin(), 0.0), 0.75, Color4::WHITE);
+        Graphics::end_mask();
+
+        // Only the parts inside the circle are drawn
+        Graphics::draw_rect(vec2(-1.55, -0.5), vec2::ONE, Color4::CYAN);
+        Graphics::draw_polygon(vec2(1.0, 0.0), 0.5, 0.0, 6, Color::PINK);
+        Graphics::clear_mask();
+
+        Graphics::draw_circle(vec2(0.0, -1.0), 0.25, Color4::YELLOW);
+        
+        window.post_tick();
+        time += window.ts();
+    }
+}
\ No newline at end of file
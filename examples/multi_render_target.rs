@@ -1,44 +1,58 @@
-use nogine::{graphics::{Graphics, pipeline::{RenderPipeline, RenderTexture, SceneRenderData, DEFAULT_RENDER_TARGET}, RenderStats, texture::TextureFiltering, BlendingMode}, window::{WindowCfg, WindowMode}, color::{Color4, Color}, math::vec2, unwrap_res};
+use nogine::{graphics::{Graphics, pipeline::{ColorFormat, RenderPipeline, RenderTexture, SceneRenderData, DEFAULT_RENDER_TARGET}, RenderStats, shader::{Shader, SubShader, SubShaderType}, preprocessor::BuiltinProvider, defaults::DefaultShaders, uniforms::Uniform, material::Material, BlendingMode, Mode}, window::{WindowCfg, WindowMode}, color::{Color4, Color}, math::vec2, unwrap_res};
 
-struct CustomPipeline {
-    intensity: f32,
-    iterations: u32,
+// Writes the color to the first attachment and the emission to the second one
+const SCENE_FRAG: &str = r#"
+#version 330 core
+
+#include "nogine/frag_data.glsl"
+
+in vec4 f_Col;
+
+uniform float emission;
+
+void main() {
+    o_FragData[0] = f_Col;
+    o_FragData[1] = vec4(f_Col.rgb * emission, 1.0);
 }
+"#;
 
-impl RenderPipeline for CustomPipeline {
-    fn render(&self, screen_rt: &mut RenderTexture, scene_data: &SceneRenderData, _ui_data: Option<&SceneRenderData>, stats: &mut RenderStats) {
-        // Render scene to texture
-        let mut src_rt = RenderTexture::sized_as(&screen_rt, TextureFiltering::Closest);
-        src_rt.clear(scene_data.clear_col());
-        src_rt.render_scene(scene_data, DEFAULT_RENDER_TARGET, stats);
-
-        // Apply effect
-        naive_blur(&src_rt, screen_rt, self.iterations, self.intensity, stats);
-
-        // Non blurred rt
-        let mut clean_rt = RenderTexture::sized_as(&screen_rt, TextureFiltering::Linear);
-        clean_rt.clear(Color4::CLEAR);
-        clean_rt.render_scene(scene_data, ALTERNATIVE_RENDER_TARGET, stats);
-        
-        // Combine
-        screen_rt.combine(&clean_rt, BlendingMode::AlphaMix, stats);
-    }
+// Adds the emission attachment on top of the color attachment
+const COMPOSITE_FRAG: &str = r#"
+#version 420 core
+
+#include "nogine/blit.glsl"
+
+uniform sampler2D emission_tex;
+
+void main() {
+    vec4 col = texture(screen_tex, f_Uv);
+    vec3 emission = texture(emission_tex, f_Uv).rgb;
+    o_Col = vec4(col.rgb + emission, col.a * f_Alpha);
 }
+"#;
 
-fn naive_blur(source: &RenderTexture, target: &mut RenderTexture, iterations: u32, intensity: f32, stats: &mut RenderStats) {
-    target.clear(Color4::CLEAR);
-    target.combine(&source, BlendingMode::AlphaMix, stats);
+struct CustomPipeline {
+    composite_shader: Shader,
+}
 
-    for i in 1..=iterations {
-        let mut downscaled_rt = source.downscaled(5 * i, TextureFiltering::Linear, stats);
-        downscaled_rt.set_alpha(intensity / i as f32);
+impl RenderPipeline for CustomPipeline {
+    fn render(&self, screen_rt: &mut RenderTexture, scene_data: &SceneRenderData, _ui_data: Option<&SceneRenderData>, stats: &mut RenderStats) {
+        // Render scene to both attachments at once
+        let mut gbuffer = RenderTexture::builder(screen_rt.res())
+            .color(ColorFormat::RGBA8)
+            .color(ColorFormat::RGBA8)
+            .depth_stencil(None)
+            .build();
+        gbuffer.clear(Color4::CLEAR);
+        gbuffer.render_scene(scene_data, DEFAULT_RENDER_TARGET, stats);
 
-        target.combine(&downscaled_rt, BlendingMode::AlphaMix, stats);
+        // Combine
+        let material = Material::new(&self.composite_shader, &[(b"emission_tex\0", Uniform::Texture(gbuffer.attachment(1).clone()))]);
+        screen_rt.clear(scene_data.clear_col());
+        screen_rt.render_with_shader(&gbuffer, &material, BlendingMode::AlphaMix, stats);
     }
 }
 
-const ALTERNATIVE_RENDER_TARGET: u8 = 1;
-
 fn main() {
     // Create Window
     let mut window = unwrap_res!(WindowCfg::default().res((1280, 720)).title("Multi Render Target Example").mode(WindowMode::Windowed).init());
@@ -46,9 +60,13 @@ fn main() {
     // Setup graphics
     Graphics::set_clear_col(Color4(0.3, 0.2, 0.1, 1.0));
 
+    let scene_frag = unwrap_res!(SubShader::preprocessed(SCENE_FRAG, SubShaderType::Frag, &[("NOGINE_FRAG_DATA_COUNT", "2")], &BuiltinProvider));
+    let scene_shader = unwrap_res!(Shader::new(&DefaultShaders::def_plain_vert(), &scene_frag));
+    let matte = Material::new(&scene_shader, &[(b"emission\0", Uniform::Float(0.0))]);
+    let glowing = Material::new(&scene_shader, &[(b"emission\0", Uniform::Float(0.75))]);
+
     let pipeline = CustomPipeline {
-        intensity: 0.75,
-        iterations: 25,
+        composite_shader: unwrap_res!(Shader::new_blit(COMPOSITE_FRAG)),
     };
     
     while window.is_running() {
@@ -56,12 +74,10 @@ fn main() {
 
         Graphics::set_cam(vec2::ZERO, vec2(1.5 * window.aspect_ratio(), 1.5));
         
+        Graphics::set_material(Some(matte.clone()), Mode::Rect);
         Graphics::draw_rect(vec2(-1.55, -0.75), vec2::ONE, Color4::CYAN);
 
-        Graphics::set_render_target(ALTERNATIVE_RENDER_TARGET);
-        Graphics::draw_circle(vec2(0.0, 0.25), 0.5, Color4::YELLOW);
-        Graphics::set_render_target(DEFAULT_RENDER_TARGET);
-
+        Graphics::set_material(Some(glowing.clone()), Mode::Rect);
         Graphics::draw_polygon(vec2(1.0, -0.25), 0.5, 0.0, 5, Color::PINK);
         
         window.post_tick();
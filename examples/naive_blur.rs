@@ -1,4 +1,4 @@
-use nogine::{graphics::{Graphics, pipeline::{RenderPipeline, RenderTexture, SceneRenderData, DEFAULT_RENDER_TARGET}, RenderStats, texture::TextureFiltering, BlendingMode}, window::{WindowCfg, WindowMode}, color::{Color4, Color}, math::vec2, unwrap_res};
+use nogine::{graphics::{Graphics, pipeline::{RenderPipeline, RenderTexture, RenderTexturePool, SceneRenderData, DEFAULT_RENDER_TARGET}, RenderStats, texture::TextureFiltering, BlendingMode}, window::{WindowCfg, WindowMode}, color::{Color4, Color}, math::vec2, unwrap_res};
 
 struct CustomPipeline {
     intensity: f32,
@@ -8,7 +8,7 @@ struct CustomPipeline {
 impl RenderPipeline for CustomPipeline {
     fn render(&self, screen_rt: &mut RenderTexture, scene_data: &SceneRenderData, _ui_data: Option<&SceneRenderData>, stats: &mut RenderStats) {
         // Render scene to texture
-        let mut src_rt = RenderTexture::sized_as(&screen_rt, TextureFiltering::Closest);
+        let mut src_rt = RenderTexturePool::get_like(&screen_rt, TextureFiltering::Closest);
         src_rt.clear(scene_data.clear_col());
         src_rt.render_scene(scene_data, DEFAULT_RENDER_TARGET, stats);
 
@@ -22,7 +22,7 @@ fn naive_blur(source: &RenderTexture, target: &mut RenderTexture, iterations: u3
     target.combine(&source, BlendingMode::AlphaMix, stats);
 
     for i in 1..=iterations {
-        let mut downscaled_rt = source.downscaled(5 * i, TextureFiltering::Linear, stats);
+        let mut downscaled_rt = source.downscaled_pooled(5 * i, TextureFiltering::Linear, stats);
         downscaled_rt.set_alpha(intensity / i as f32);
 
         target.combine(&downscaled_rt, BlendingMode::AlphaMix, stats);
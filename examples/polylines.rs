@@ -0,0 +1,39 @@
+use nogine::{color::{Color, Color4}, graphics::{polyline::{LineCap, LineJoin, LineStyle}, Graphics}, math::vec2, unwrap_res, window::{WindowCfg, WindowMode}};
+
+fn main() {
+    // Create Window
+    let mut window = unwrap_res!(WindowCfg::default().res((1280, 720)).title("Polylines Example").mode(WindowMode::Windowed).init());
+
+    let zigzag = [vec2(-0.6, -0.3), vec2(-0.3, 0.3), vec2(0.0, -0.3), vec2(0.3, 0.3), vec2(0.6, -0.3)];
+    let mut time = 0.0;
+    while window.is_running() {
+        window.pre_tick(None);
+        time += window.ts();
+
+        Graphics::set_cam(vec2::ZERO, vec2(2.0 * window.aspect_ratio(), 2.0));
+
+        // Joins and caps
+        let joins = [(LineJoin::Miter, LineCap::Butt), (LineJoin::Round, LineCap::Round), (LineJoin::Bevel, LineCap::Square)];
+        for (i, &(join, cap)) in joins.iter().enumerate() {
+            let offset = vec2(i as f32 * 1.5 - 1.5, 1.0);
+            let points = zigzag.map(|x| x + offset);
+            Graphics::draw_polyline(&points, 0.15, join, cap, &[Color4::CYAN]);
+        }
+
+        // Closed, with a gradient
+        let star = (0..10).map(|i| {
+            let radius = if i % 2 == 0 { 0.7 } else { 0.3 };
+            let theta = i as f32 * std::f32::consts::PI / 5.0;
+            vec2(theta.sin(), theta.cos()) * radius + vec2(-1.5, -0.9)
+        }).collect::<Vec<_>>();
+        let colors = (0..10).map(|i| Color4::YELLOW.mix(Color4::RED, i as f32 / 9.0)).collect::<Vec<_>>();
+        Graphics::draw_polyline_ext(&star, &LineStyle { closed: true, ..LineStyle::new(0.08, LineJoin::Miter, LineCap::Butt) }, &colors);
+
+        // Animated dashes
+        let wave = (0..=40).map(|i| vec2(i as f32 * 0.06, (i as f32 * 0.3 + time).sin() * 0.3) + vec2(-0.3, -0.9)).collect::<Vec<_>>();
+        let style = LineStyle { dash: vec![0.2, 0.1], dash_offset: -time, ..LineStyle::new(0.06, LineJoin::Round, LineCap::Round) };
+        Graphics::draw_polyline_ext(&wave, &style, &[Color4::PINK]);
+
+        window.post_tick();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,56 @@
+use nogine::{graphics::{Graphics, pipeline::{ColorFormat, RenderPipeline, RenderTexture, SceneRenderData, DEFAULT_RENDER_TARGET}, post::{Bloom, ChromaticAberration, Crt, Dither, GaussianBlur, Pixelate, PostEffect, Vignette}, RenderStats, texture::TextureFiltering}, input::{Input, KeyInput}, window::{WindowCfg, WindowMode}, color::{Color4, Color}, math::vec2, log_info, unwrap_res};
+
+struct PostPipeline {
+    effects: Vec<(&'static str, Box<dyn PostEffect>)>,
+    current: usize,
+}
+
+impl RenderPipeline for PostPipeline {
+    fn render(&self, screen_rt: &mut RenderTexture, scene_data: &SceneRenderData, _ui_data: Option<&SceneRenderData>, stats: &mut RenderStats) {
+        // Render scene to texture
+        let mut src_rt = RenderTexture::builder(screen_rt.res()).filtering(TextureFiltering::Linear).color(ColorFormat::RGBA16F).build();
+        src_rt.clear(scene_data.clear_col());
+        src_rt.render_scene(scene_data, DEFAULT_RENDER_TARGET, stats);
+
+        // Apply the selected effect
+        self.effects[self.current].1.apply(&src_rt, screen_rt, stats);
+    }
+}
+
+fn main() {
+    // Create Window
+    let mut window = unwrap_res!(WindowCfg::default().res((1280, 720)).title("Post Processing Example. Space: Next effect").mode(WindowMode::Windowed).init());
+
+    // Setup graphics
+    Graphics::set_clear_col(Color4(0.3, 0.2, 0.1, 1.0));
+
+    let mut pipeline = PostPipeline {
+        effects: vec![
+            ("Gaussian blur", Box::new(GaussianBlur { sigma: 6.0 })),
+            ("Bloom", Box::new(Bloom::default())),
+            ("Vignette", Box::new(Vignette::default())),
+            ("Chromatic aberration", Box::new(ChromaticAberration { offset: 8.0 })),
+            ("CRT", Box::new(Crt::default())),
+            ("Pixelate", Box::new(Pixelate { pixel_size: 8 })),
+            ("Dither", Box::new(Dither { levels: 3, scale: 2 })),
+        ],
+        current: 0,
+    };
+    
+    while window.is_running() {
+        window.pre_tick(Some(&pipeline));
+
+        if Input::key_pressed(KeyInput::Space) {
+            pipeline.current = (pipeline.current + 1) % pipeline.effects.len();
+            log_info!("Effect: {}", pipeline.effects[pipeline.current].0);
+        }
+
+        Graphics::set_cam(vec2::ZERO, vec2(1.5 * window.aspect_ratio(), 1.5));
+        
+        Graphics::draw_rect(vec2(-1.55, -0.75), vec2::ONE, Color4::CYAN);
+        Graphics::draw_circle(vec2(0.0, 0.25), 0.5, Color4(3.0, 2.5, 0.5, 1.0));
+        Graphics::draw_polygon(vec2(1.0, -0.25), 0.5, 0.0, 5, Color::PINK);
+        
+        window.post_tick();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,48 @@
+use nogine::{graphics::{Graphics, defaults::DefaultMaterials, pipeline::{ColorFormat, ToneMapping, DEFAULT_RENDER_TARGET}, post::{Bloom, GaussianBlur}, render_graph::{Pass, RenderGraph, TargetDesc, SCREEN}, BlendingMode}, window::{WindowCfg, WindowMode}, color::{Color4, Color}, math::vec2, log_info, unwrap_res};
+
+const OVERLAY_RENDER_TARGET: u8 = 1;
+
+fn main() {
+    // Create Window
+    let mut window = unwrap_res!(WindowCfg::default().res((1280, 720)).title("Render Graph Example").mode(WindowMode::Windowed).init());
+
+    // Setup graphics
+    Graphics::set_clear_col(Color4(0.1, 0.1, 0.15, 1.0));
+
+    let hdr = TargetDesc { format: ColorFormat::RGBA16F, ..Default::default() };
+    let graph = unwrap_res!(RenderGraph::builder()
+        .target("scene", hdr)
+        .target("bloomed", hdr)
+        .target("debug", TargetDesc::default())
+        .pass(Pass::scene("world", DEFAULT_RENDER_TARGET).write("scene").clear_to_scene())
+        .pass(Pass::effect("bloom", Bloom { threshold: 0.8, ..Default::default() }).read("scene").write("bloomed"))
+        .pass(Pass::blit("tonemap", DefaultMaterials::tonemap_material(ToneMapping::Aces, 1.0), BlendingMode::AlphaMix).read("bloomed").write(SCREEN))
+        .pass(Pass::scene("overlay", OVERLAY_RENDER_TARGET))
+        .pass(Pass::ui("ui"))
+        // Nothing reads this one, so it's culled
+        .pass(Pass::effect("debug_blur", GaussianBlur::default()).read("scene").write("debug"))
+        .build());
+
+    let mut frame = 0;
+    while window.is_running() {
+        window.pre_tick(Some(&graph));
+
+        frame += 1;
+        if frame % 120 == 0 {
+            for pass in graph.last_stats() {
+                log_info!("{}: {} draw calls{}", pass.name, pass.stats.draw_calls(), if pass.culled { " (culled)" } else { "" });
+            }
+        }
+
+        Graphics::set_cam(vec2::ZERO, vec2(1.5 * window.aspect_ratio(), 1.5));
+        
+        Graphics::draw_rect(vec2(-1.55, -0.75), vec2::ONE, Color4::CYAN);
+        Graphics::draw_circle(vec2(0.0, 0.25), 0.5, Color4(3.0, 2.5, 0.5, 1.0));
+
+        Graphics::set_render_target(OVERLAY_RENDER_TARGET);
+        Graphics::draw_polygon(vec2(1.0, -0.25), 0.5, 0.0, 5, Color::PINK);
+        Graphics::set_render_target(DEFAULT_RENDER_TARGET);
+        
+        window.post_tick();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,15 @@
+#version 330 core
+
+#include "nogine/globals.glsl"
+
+layout (location = 0) out vec4 o_Col;
+
+in vec4 f_Col;
+
+uniform vec3 tint;
+
+void main() {
+    // Edit this file while the example is running
+    float t = sin(g_Time) * 0.5 + 0.5;
+    o_Col = vec4(mix(f_Col.rgb, tint, t), f_Col.a);
+}
\ No newline at end of file
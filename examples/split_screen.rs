@@ -0,0 +1,52 @@
+use nogine::{color::{Color, Color4}, graphics::{camera::{Camera2D, View}, CamData, Graphics}, input::{Input, KeyInput, MouseInput}, log_info, math::{vec2, Rect}, unwrap_res, window::{WindowCfg, WindowMode}};
+
+fn main() {
+    // Create Window
+    let mut window = unwrap_res!(WindowCfg::default().res((1280, 720)).title("Split Screen Example").mode(WindowMode::Windowed).init());
+
+    // Each half of the screen has half of the aspect ratio
+    let half_size = vec2(2.5 * window.aspect_ratio(), 5.0);
+    let mut cams = [Camera2D::new(vec2(-5.0, 0.0), half_size), Camera2D::new(vec2(5.0, 0.0), half_size)];
+    for cam in &mut cams {
+        cam.smoothing = 0.1;
+    }
+
+    let mut players = [vec2(-5.0, 0.0), vec2(5.0, 0.0)];
+    while window.is_running() {
+        window.pre_tick(None);
+
+        // Move players
+        players[0] = players[0] + vec2(Input::axis(KeyInput::A, KeyInput::D) as f32, Input::axis(KeyInput::S, KeyInput::W) as f32) * (8.0 * window.ts());
+        players[1] = players[1] + vec2(Input::axis(KeyInput::Left, KeyInput::Right) as f32, Input::axis(KeyInput::Down, KeyInput::Up) as f32) * (8.0 * window.ts());
+
+        for (cam, player) in cams.iter_mut().zip(players.iter()) {
+            cam.follow(*player);
+            cam.update(window.ts());
+        }
+
+        // Left and right halves, with a minimap on top
+        Graphics::set_views(&[
+            cams[0].view(Rect { start: vec2(0.0, 0.0), end: vec2(0.5, 1.0) }),
+            cams[1].view(Rect { start: vec2(0.5, 0.0), end: vec2(1.0, 1.0) }),
+            View::new(CamData::new(vec2::ZERO, vec2(20.0, 20.0), 0.0), Rect { start: vec2(0.4, 0.02), end: vec2(0.6, 0.02 + 0.2 * 16.0 / 9.0) }).clear_col(Some(Color4::BLACK)),
+        ]);
+
+        if Input::mouse_pressed(MouseInput::Left) {
+            if let Some((view, pos)) = Graphics::view_screen_to_world(Input::mouse_pos(), window.get_size()) {
+                log_info!("Clicked {pos} in view {view}");
+            }
+        }
+
+        // Draw level once, every view renders it
+        for x in -6..=6 {
+            for y in -6..=6 {
+                Graphics::draw_circle(vec2(x as f32 * 3.0, y as f32 * 3.0), 0.4, Color4::GRAY);
+            }
+        }
+
+        Graphics::draw_rect(players[0] - vec2(0.25, 0.25), vec2(0.5, 0.5), Color4::CYAN);
+        Graphics::draw_rect(players[1] - vec2(0.25, 0.25), vec2(0.5, 0.5), Color4::PINK);
+
+        window.post_tick();
+    }
+}
\ No newline at end of file
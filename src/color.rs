@@ -21,7 +21,7 @@ pub trait Color {
 }
 
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Color4(pub f32, pub f32, pub f32, pub f32);
 
 impl Color4 {
@@ -1,8 +1,78 @@
 use std::sync::Arc;
 
-use crate::{graphics::verts::set_vertex_attribs, math::mat3, assert_expr, utils::ptr_slice::PtrSlice};
+use crate::{graphics::{buffers::bind_unit_quad, consts::MAX_TEXTURE_SLOTS, verts::{set_instance_attribs, set_vertex_attribs}}, math::{mat3, vec2}, assert_expr, utils::ptr_slice::PtrSlice};
+
+use super::{buffers::{BufferPool, StreamBuffers}, gl_bindings::{gl_clear_stencil, gl_set_scissor, gl_set_stencil, GlStencilMode}, gl_call, material::Material, pipeline::ScreenRect, texture::{Texture, TextureCore}, BlendingMode, LayerSorting};
+
+/// Defines how a batch interacts with the stencil mask.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MaskMode {
+    /// The mask is ignored.
+    Disabled,
+    /// The batch writes into the mask instead of the color buffer. The mask is cleared before the first batch with a given `id`.
+    Write { id: u32, alpha_cutoff: f32 },
+    /// The batch is clipped by the mask.
+    Test,
+}
+
+impl MaskMode {
+    /// Sets up the stencil for the mode. Must be called before the scissor is set for the batch.
+    /// - A new mask is cleared across the whole `viewport`, so mask bits left outside of the clip rect of the batch don't leak into it.
+    fn apply(&self, last_write: &mut Option<u32>, viewport: &ScreenRect) {
+        match self {
+            MaskMode::Disabled => gl_set_stencil(GlStencilMode::Disabled),
+            MaskMode::Write { id, .. } => {
+                gl_set_stencil(GlStencilMode::Write);
+                if *last_write != Some(*id) {
+                    gl_set_scissor(Some((viewport.l(), viewport.d(), viewport.r() - viewport.l(), viewport.u() - viewport.d())));
+                    gl_clear_stencil();
+                    *last_write = Some(*id);
+                }
+            },
+            MaskMode::Test => gl_set_stencil(GlStencilMode::Test),
+        }
+    }
+}
+
+/// Axis-aligned clipping rect, in the same space as the vertex data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipRect {
+    pub min: vec2,
+    pub max: vec2,
+}
+
+impl ClipRect {
+    pub fn from_points(points: &[vec2]) -> Self {
+        let mut min = vec2(f32::INFINITY, f32::INFINITY);
+        let mut max = vec2(f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for p in points {
+            min = vec2(min.0.min(p.0), min.1.min(p.1));
+            max = vec2(max.0.max(p.0), max.1.max(p.1));
+        }
+
+        return Self { min, max };
+    }
+
+    pub fn intersect(&self, other: &Self) -> Self {
+        let min = vec2(self.min.0.max(other.min.0), self.min.1.max(other.min.1));
+        let max = vec2(self.max.0.min(other.max.0), self.max.1.min(other.max.1));
+        return Self { min, max: vec2(max.0.max(min.0), max.1.max(min.1)) };
+    }
+
+    /// Returns the rect in framebuffer pixels, as `(x, y, width, height)`, clipped to `viewport`.
+    fn to_screen(&self, cam: &mat3, viewport: &ScreenRect) -> (i32, i32, i32, i32) {
+        let a = cam * self.min;
+        let b = cam * self.max;
+
+        let to_px = |ndc: f32, start: i32, end: i32| start as f32 + (ndc * 0.5 + 0.5) * (end - start) as f32;
+        let l = (to_px(a.0.min(b.0), viewport.l(), viewport.r()).floor() as i32).max(viewport.l());
+        let r = (to_px(a.0.max(b.0), viewport.l(), viewport.r()).ceil() as i32).min(viewport.r());
+        let d = (to_px(a.1.min(b.1), viewport.d(), viewport.u()).floor() as i32).max(viewport.d());
+        let u = (to_px(a.1.max(b.1), viewport.d(), viewport.u()).ceil() as i32).min(viewport.u());
 
-use super::{buffers::GlVAO, gl_bindings::buffer::{GlBuffer, GlBufferKind, GlBufferUsage}, gl_call, material::Material, texture::{Texture, TextureCore}, BlendingMode};
+        return (l, d, (r - l).max(0), (u - d).max(0));
+    }
+}
 
 pub struct RefBatchState {
     pub material: Material,
@@ -10,6 +80,12 @@ pub struct RefBatchState {
     pub textures: PtrSlice<*const Texture>,
     pub blending: BlendingMode,
     pub is_line: bool,
+    pub instanced: bool,
+    pub multi_texture: bool,
+    pub mask: MaskMode,
+    pub clip: Option<ClipRect>,
+    pub layer: i32,
+    pub sort_key: f32,
 }
 
 impl Into<BatchState> for RefBatchState {
@@ -19,23 +95,44 @@ impl Into<BatchState> for RefBatchState {
             self.attribs.as_slice().into(),
             self.textures.iter().map(|&x| unsafe { x.as_ref().unwrap_unchecked() }.clone_core()).collect(),
             self.blending,
-            self.is_line
+            self.is_line,
+            self.instanced,
+            self.multi_texture,
+            self.mask,
+            self.clip,
         );
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BatchState {
     material: Material,
     attribs: Box<[usize]>,
-    textures: Box<[Arc<TextureCore>]>,
+    textures: Vec<Arc<TextureCore>>,
     blending: BlendingMode,
     is_line: bool,
+    instanced: bool,
+    multi_texture: bool,
+    mask: MaskMode,
+    clip: Option<ClipRect>,
 }
 
 impl BatchState {
-    fn new<'a>(material: Material, attribs: Box<[usize]>, textures: Box<[Arc<TextureCore>]>, blending: BlendingMode, is_line: bool) -> Self {
-        return Self { material, attribs, textures, blending, is_line };
+    fn new<'a>(material: Material, attribs: Box<[usize]>, textures: Vec<Arc<TextureCore>>, blending: BlendingMode, is_line: bool, instanced: bool, multi_texture: bool, mask: MaskMode, clip: Option<ClipRect>) -> Self {
+        return Self { material, attribs, textures, blending, is_line, instanced, multi_texture, mask, clip };
+    }
+
+    /// Multi-textured states match regardless of their textures, slot availability is checked by `BatchMesh::accepts`.
+    fn matches(&self, other: &BatchState) -> bool {
+        return self.attribs == other.attribs &&
+            self.blending == other.blending &&
+            self.material == other.material &&
+            self.is_line == other.is_line &&
+            self.instanced == other.instanced &&
+            self.multi_texture == other.multi_texture &&
+            self.mask == other.mask &&
+            self.clip == other.clip &&
+            (self.multi_texture || self.textures == other.textures);
     }
 }
 
@@ -58,14 +155,56 @@ impl BatchMesh {
         self.tris.extend(tris.iter().map(|x| *x + voffset as u32));
     }
 
-    pub fn consume(self) -> BatchProduct {
-        let vao = GlVAO::new();
-        vao.bind();
+    /// Pushes a draw, assigning its texture to a slot if the batch is multi-textured.<br>
+    /// The last attrib of every vertex is overwritten with the slot index.
+    pub fn push_draw(&mut self, verts: &[f32], tris: &[u32], textures: &[Arc<TextureCore>]) {
+        if !self.state.multi_texture {
+            self.push(verts, tris);
+            return;
+        }
+
+        assert_expr!(textures.len() == 1, "Multi-textured draws must use exactly one texture.");
+        let slot = self.assign_slot(&textures[0]);
+
+        let stride = self.state.attribs.iter().sum::<usize>();
+        let mut verts = verts.to_vec();
+        for v in verts.chunks_mut(stride) {
+            v[stride - 1] = slot as f32;
+        }
 
-        let vbo = GlBuffer::new(&self.verts, GlBufferKind::VBO, GlBufferUsage::StaticDraw);
-        let ebo = GlBuffer::new(&self.tris, GlBufferKind::EBO, GlBufferUsage::StaticDraw);
+        self.push(&verts, tris);
+    }
+
+    /// Returns the slot of a texture, taking a new one if it isn't in the batch yet.
+    fn assign_slot(&mut self, tex: &Arc<TextureCore>) -> usize {
+        let slot = self.texture_slot(tex).unwrap();
+        if slot == self.state.textures.len() {
+            self.state.textures.push(tex.clone());
+        }
+
+        return slot;
+    }
+
+    /// Returns the slot a texture would use in this batch, or `None` if all slots are taken.
+    fn texture_slot(&self, tex: &TextureCore) -> Option<usize> {
+        return match self.state.textures.iter().position(|x| x.as_ref() == tex) {
+            Some(i) => Some(i),
+            None if self.state.textures.len() + self.state.material.texture_count() < MAX_TEXTURE_SLOTS => Some(self.state.textures.len()),
+            None => None,
+        };
+    }
 
-        return BatchProduct { vao, vbo, ebo, trilen: self.tris.len() as i32, state: self.state };
+    pub fn accepts(&self, state: &BatchState) -> bool {
+        return self.state.matches(state) && (!self.state.multi_texture || state.textures.iter().all(|x| self.texture_slot(x).is_some()));
+    }
+
+    pub fn consume(self, pool: &mut BufferPool) -> BatchProduct {
+        let mut buffers = pool.take();
+        buffers.upload(&self.verts, &self.tris);
+
+        let instances = if self.state.instanced { (self.verts.len() / self.state.attribs.iter().sum::<usize>()) as i32 } else { 0 };
+
+        return BatchProduct { buffers, trilen: self.tris.len() as i32, instances, state: self.state };
     }
 
     pub fn is_of_state(&self, state: &RefBatchState) -> bool {
@@ -73,43 +212,77 @@ impl BatchMesh {
             self.state.blending == state.blending &&
             self.state.material == state.material &&
             self.state.is_line == state.is_line &&
-            self.state.textures.iter().map(|x| x.as_ref()).eq(state.textures.iter().map(|x| unsafe { x.as_ref().unwrap_unchecked() }.core()));
+            self.state.instanced == state.instanced &&
+            self.state.multi_texture == state.multi_texture &&
+            self.state.mask == state.mask &&
+            self.state.clip == state.clip &&
+            if state.multi_texture {
+                state.textures.iter().all(|x| self.texture_slot(unsafe { x.as_ref().unwrap_unchecked() }.core()).is_some())
+            } else {
+                self.state.textures.iter().map(|x| x.as_ref()).eq(state.textures.iter().map(|x| unsafe { x.as_ref().unwrap_unchecked() }.core()))
+            };
     }
 }
 
 
 // Is produced in post-tick, rendered in pre-tick
 pub struct BatchProduct {
-    vao: GlVAO,
-    vbo: GlBuffer,
-    ebo: GlBuffer,
+    buffers: StreamBuffers,
     trilen: i32,
+    instances: i32,
     state: BatchState,
 }
 
 impl BatchProduct {
-    pub fn render(&self, cam: &mat3) {
-        self.vao.bind();
-        self.vbo.bind();
-        self.ebo.bind();
+    pub fn uses_mask(&self) -> bool {
+        return self.state.mask != MaskMode::Disabled;
+    }
 
-        set_vertex_attribs(&self.state.attribs);
+    /// Renders the batch.
+    /// - `viewport` is the area of the target being rendered into, used to place the clip rect.
+    /// - `last_mask_write` keeps track of the last mask written, so the stencil is only cleared when a new mask begins.
+    pub fn render(&self, cam: &mat3, viewport: &ScreenRect, last_mask_write: &mut Option<u32>) {
+        self.buffers.vao.bind();
+
+        let index_count = if self.state.instanced {
+            let index_count = bind_unit_quad();
+            self.buffers.vbo.bind();
+            set_instance_attribs(&self.state.attribs, 1);
+            index_count
+        } else {
+            self.buffers.bind();
+            set_vertex_attribs(&self.state.attribs);
+            self.trilen
+        };
 
         for (i, t) in self.state.textures.iter().enumerate() {
             t.enable(i as u8);
         }
 
-        self.state.material.enable();
+        self.state.material.enable(self.state.textures.len() as u8);
 
         let tf_mat_address = gl_call!(gl::GetUniformLocation(self.state.material.shader().id(), b"mvm\0".as_ptr() as *const i8));
         assert_expr!(tf_mat_address != -1, "Can't find 'mvm' uniform in shader.");
         gl_call!(gl::UniformMatrix3fv(tf_mat_address, 1, gl::TRUE, cam.ptr()));
 
+        let alpha_cutoff_address = gl_call!(gl::GetUniformLocation(self.state.material.shader().id(), b"alpha_cutoff\0".as_ptr() as *const i8));
+        if alpha_cutoff_address != -1 {
+            let alpha_cutoff = if let MaskMode::Write { alpha_cutoff, .. } = self.state.mask { alpha_cutoff } else { 0.0 };
+            gl_call!(gl::Uniform1f(alpha_cutoff_address, alpha_cutoff));
+        }
+
         self.state.blending.apply();
+        self.state.mask.apply(last_mask_write, viewport);
+        gl_set_scissor(self.state.clip.map(|x| x.to_screen(cam, viewport)));
+
+        if self.state.instanced {
+            gl_call!(gl::DrawElementsInstanced(gl::TRIANGLES, index_count, gl::UNSIGNED_INT, std::ptr::null(), self.instances));
+            return;
+        }
 
         gl_call!(gl::DrawElements(
             if self.state.is_line { gl::LINES } else { gl::TRIANGLES },
-            self.trilen,
+            index_count,
             gl::UNSIGNED_INT,
             std::ptr::null()
         ));
@@ -117,73 +290,187 @@ impl BatchProduct {
 }
 
 
+/// A draw waiting to be sorted before being batched.
+struct PendingDraw {
+    key: f32,
+    bounds: internal::Bounds,
+    verts: Box<[f32]>,
+    tris: Box<[u32]>,
+    state: BatchState,
+}
+
+struct LayerBatchData {
+    layer: i32,
+    curr_batch: Option<BatchMesh>,
+    pending: Vec<PendingDraw>,
+    ready_batches: Vec<BatchProduct>,
+}
+
+impl LayerBatchData {
+    const fn new(layer: i32) -> Self {
+        return Self { layer, curr_batch: None, pending: Vec::new(), ready_batches: Vec::new() };
+    }
+
+    fn finalize_curr(&mut self, pool: &mut BufferPool) {
+        if let Some(x) = self.curr_batch.take() {
+            self.ready_batches.push(x.consume(pool));
+        }
+    }
+
+    /// Batches the pending draws merging compatible states, while keeping the relative order of overlapping draws.<br>
+    /// Returns the number of draw calls saved compared to batching in submission order.
+    fn flush_reordered(&mut self, pool: &mut BufferPool) -> usize {
+        let pending = std::mem::take(&mut self.pending);
+        let naive_count = internal::submission_batch_count(&pending);
+        let buckets = internal::reorder(pending);
+
+        // Merging is greedy, so it can end up with more batches when texture slots run out
+        let saved = naive_count.saturating_sub(buckets.len());
+        self.finalize_curr(pool);
+        for (_, mesh) in buckets {
+            self.ready_batches.push(mesh.consume(pool));
+        }
+
+        return saved;
+    }
+
+    fn flush_pending(&mut self, pool: &mut BufferPool) {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.sort_by(|a, b| a.key.total_cmp(&b.key)); // Stable, so equal keys keep submission order
+
+        for p in pending {
+            if self.curr_batch.as_ref().map_or(false, |x| !x.accepts(&p.state)) {
+                self.finalize_curr(pool);
+            }
+
+            let textures = p.state.textures.clone();
+            self.curr_batch.get_or_insert_with(|| BatchMesh::new(p.state)).push_draw(&p.verts, &p.tris, &textures);
+        }
+    }
+}
+
 pub(super) struct TargetBatchData {
-    pub curr_batch: Option<BatchMesh>,
+    layers: Vec<LayerBatchData>, // Ordered by layer
     pub ready_batches: Vec<BatchProduct>,
     pub render_batches: Vec<BatchProduct>,
+    ready_saved_calls: usize,
+    pub render_saved_calls: usize,
 }
 
 impl TargetBatchData {
     const fn new() -> Self {
-        return Self { curr_batch: None, ready_batches: Vec::new(), render_batches: Vec::new() }
+        return Self { layers: Vec::new(), ready_batches: Vec::new(), render_batches: Vec::new(), ready_saved_calls: 0, render_saved_calls: 0 }
+    }
+
+    fn realize_layer(&mut self, layer: i32) -> &mut LayerBatchData {
+        let i = match self.layers.binary_search_by_key(&layer, |x| x.layer) {
+            Ok(i) => i,
+            Err(i) => {
+                self.layers.insert(i, LayerBatchData::new(layer));
+                i
+            },
+        };
+        return &mut self.layers[i];
     }
 }
 
 pub(super) struct BatchData {
     pub targets: Vec<(u8, TargetBatchData)>,
+    sorting: Vec<(i32, LayerSorting)>,
+    pool: BufferPool,
 }
 
 impl BatchData {
     pub const fn new() -> Self {
         return Self {
-            targets: Vec::new()
+            targets: Vec::new(),
+            sorting: Vec::new(),
+            pool: BufferPool::new(),
         }
     }
 
     pub fn send(&mut self, target_id: u8, state: RefBatchState, verts: &[f32], tris: &[u32]) {
-        self.check_state(target_id, &state);
-
-        let target = self.realize_target(target_id);
+        let layer = state.layer;
+        let sorting = self.get_sorting(layer);
         
-        if target.curr_batch.is_none() {
-            target.curr_batch = Some(BatchMesh::new(state.into()));
+        if !matches!(sorting, LayerSorting::Submission) {
+            let key = match sorting {
+                LayerSorting::Key => state.sort_key,
+                _ => internal::lowest_point_key(verts, state.attribs.as_slice()),
+            };
+            let bounds = internal::Bounds::from_verts(verts, state.attribs.as_slice(), state.instanced);
+
+            let target = self.realize_target(target_id).realize_layer(layer);
+            target.pending.push(PendingDraw { key, bounds, verts: verts.into(), tris: tris.into(), state: state.into() });
+            return;
         }
 
-        target.curr_batch.as_mut().unwrap().push(verts, tris);
+        self.check_state(target_id, &state);
 
+        let textures = if state.multi_texture {
+            state.textures.iter().map(|&x| unsafe { x.as_ref().unwrap_unchecked() }.clone_core()).collect()
+        } else {
+            Vec::new()
+        };
+
+        let layer = self.realize_target(target_id).realize_layer(layer);
+        if layer.curr_batch.is_none() {
+            layer.curr_batch = Some(BatchMesh::new(state.into()));
+        }
+
+        layer.curr_batch.as_mut().unwrap().push_draw(verts, tris, &textures);
     }
 
     pub fn check_state(&mut self, target_id: u8, state: &RefBatchState) {
-        if let Some(target) = self.get(target_id) {
-            if target.curr_batch.is_none() {
-                return;
-            }
-            
-            if !target.curr_batch.as_ref().unwrap().is_of_state(&state) {
-                self.finalize_batch(target_id);
+        let pool = &mut self.pool;
+        if let Some(target) = self.targets.iter_mut().find(|x| x.0 == target_id).map(|x| &mut x.1) {
+            if let Some(layer) = target.layers.iter_mut().find(|x| x.layer == state.layer) {
+                if layer.curr_batch.as_ref().map_or(false, |x| !x.is_of_state(state)) {
+                    layer.finalize_curr(pool);
+                }
             }
         }
     }
 
+    /// Finalizes every layer of the target, leaving its batches ordered by layer.
     pub fn finalize_batch(&mut self, target_id: u8) {
-        if let Some(target) = self.get_mut(target_id) {    
-            let mut batch: Option<BatchMesh> = None;
-            std::mem::swap(&mut batch, &mut target.curr_batch);
-            
-            if let Some(x) = batch {
-                let product = x.consume();
-                target.ready_batches.push(product);
+        let pool = &mut self.pool;
+        if let Some(target) = self.targets.iter_mut().find(|x| x.0 == target_id).map(|x| &mut x.1) {
+            for layer in &mut target.layers {
+                if matches!(self.sorting.iter().find(|x| x.0 == layer.layer).map(|x| x.1), Some(LayerSorting::Reorder)) {
+                    target.ready_saved_calls += layer.flush_reordered(pool);
+                } else {
+                    layer.flush_pending(pool);
+                }
+                layer.finalize_curr(pool);
+                target.ready_batches.append(&mut layer.ready_batches);
             }
         }
     }
 
     pub fn swap_batch_buffers(&mut self, target_id: u8) {
-        if let Some(target) = self.get_mut(target_id) {
+        if let Some(target) = self.targets.iter_mut().find(|x| x.0 == target_id).map(|x| &mut x.1) {
             std::mem::swap(&mut target.ready_batches, &mut target.render_batches);
-            target.ready_batches.clear();
+            target.render_saved_calls = std::mem::take(&mut target.ready_saved_calls);
+            
+            // The batches that were just rendered give their buffers back to the pool
+            for b in target.ready_batches.drain(..) {
+                self.pool.recycle(b.buffers);
+            }
+        }
+    }
+
+    pub fn set_sorting(&mut self, layer: i32, sorting: LayerSorting) {
+        match self.sorting.iter().position(|x| x.0 == layer) {
+            Some(i) => self.sorting[i].1 = sorting,
+            None => self.sorting.push((layer, sorting)),
         }
     }
 
+    pub fn get_sorting(&self, layer: i32) -> LayerSorting {
+        return self.sorting.iter().find(|x| x.0 == layer).map_or(LayerSorting::Submission, |x| x.1);
+    }
+
     fn realize_target(&mut self, target: u8) -> &mut TargetBatchData {        
         if let Some(i) = self.targets.iter().position(|x| x.0 == target) {
             return &mut self.targets[i].1;
@@ -203,8 +490,144 @@ impl BatchData {
 
     pub fn clear(&mut self) {
         for t in &mut self.targets {
-            t.1.curr_batch = None;
-            t.1.ready_batches.clear();
+            for layer in &mut t.1.layers {
+                layer.curr_batch = None;
+                layer.pending.clear();
+                
+                for b in layer.ready_batches.drain(..) {
+                    self.pool.recycle(b.buffers);
+                }
+            }
+
+            for b in t.1.ready_batches.drain(..) {
+                self.pool.recycle(b.buffers);
+            }
+            t.1.ready_saved_calls = 0;
+        }
+    }
+}
+
+mod internal {
+    use super::{BatchMesh, PendingDraw};
+
+    /// Number of previous batches a draw can be merged into when reordering.
+    const LOOKBACK: usize = 32;
+
+    /// Groups the draws into batches, moving each one back to the latest compatible batch it doesn't need to be drawn over.
+    pub fn reorder(pending: Vec<PendingDraw>) -> Vec<(Bounds, BatchMesh)> {
+        let mut buckets: Vec<(Bounds, BatchMesh)> = Vec::new();
+        'outer: for p in pending {
+            for i in (buckets.len().saturating_sub(LOOKBACK)..buckets.len()).rev() {
+                let (bounds, mesh) = &mut buckets[i];
+                if mesh.accepts(&p.state) {
+                    *bounds = bounds.union(&p.bounds);
+                    mesh.push_draw(&p.verts, &p.tris, &p.state.textures);
+                    continue 'outer;
+                }
+
+                if bounds.overlaps(&p.bounds) {
+                    break; // Moving the draw further back would change the result
+                }
+            }
+
+            let textures = p.state.textures.clone();
+            let mut mesh = BatchMesh::new(p.state);
+            mesh.push_draw(&p.verts, &p.tris, &textures);
+            buckets.push((p.bounds, mesh));
+        }
+
+        return buckets;
+    }
+
+    /// Returns the number of batches the draws would take in submission order, with the same texture slot limits as `reorder`.
+    pub fn submission_batch_count(pending: &[PendingDraw]) -> usize {
+        let mut count = 0;
+        let mut curr: Option<BatchMesh> = None;
+        for p in pending {
+            if !curr.as_ref().is_some_and(|x| x.accepts(&p.state)) {
+                count += 1;
+                curr = Some(BatchMesh::new(p.state.clone()));
+            }
+
+            if p.state.multi_texture {
+                curr.as_mut().unwrap().assign_slot(&p.state.textures[0]);
+            }
         }
+
+        return count;
+    }
+
+    /// Axis aligned bounds of a draw, as `[min_x, min_y, max_x, max_y]`.
+    #[derive(Clone, Copy)]
+    pub struct Bounds([f32; 4]);
+
+    impl Bounds {
+        pub fn from_verts(verts: &[f32], attribs: &[usize], instanced: bool) -> Self {
+            let stride = attribs.iter().sum::<usize>().max(1);
+            let mut bounds = [f32::MAX, f32::MAX, f32::MIN, f32::MIN];
+            let mut push = |x: f32, y: f32| {
+                bounds = [bounds[0].min(x), bounds[1].min(y), bounds[2].max(x), bounds[3].max(y)];
+            };
+
+            for v in verts.chunks(stride) {
+                if instanced {
+                    // Instances start with [origin, axis_x, axis_y]
+                    let (o, ax, ay) = ((v[0], v[1]), (v[2], v[3]), (v[4], v[5]));
+                    push(o.0, o.1);
+                    push(o.0 + ax.0, o.1 + ax.1);
+                    push(o.0 + ay.0, o.1 + ay.1);
+                    push(o.0 + ax.0 + ay.0, o.1 + ax.1 + ay.1);
+                } else {
+                    push(v[0], v[1]);
+                }
+            }
+
+            return Self(bounds);
+        }
+
+        pub fn union(&self, other: &Self) -> Self {
+            let (a, b) = (self.0, other.0);
+            return Self([a[0].min(b[0]), a[1].min(b[1]), a[2].max(b[2]), a[3].max(b[3])]);
+        }
+
+        pub fn overlaps(&self, other: &Self) -> bool {
+            let (a, b) = (self.0, other.0);
+            return a[0] < b[2] && b[0] < a[2] && a[1] < b[3] && b[1] < a[3];
+        }
+    }
+
+    /// Returns the sorting key for y-sorting, which is the highest y of the vertices (y is flipped at this point).
+    pub fn lowest_point_key(verts: &[f32], attribs: &[usize]) -> f32 {
+        let stride = attribs.iter().sum::<usize>();
+        return verts.iter().skip(1).step_by(stride.max(1)).fold(f32::MIN, |accum, y| accum.max(*y));
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::{graphics::{consts::MAX_TEXTURE_SLOTS, material::Material, texture::Texture, BlendingMode}, math::uvec2};
+
+    use super::{internal, BatchState, MaskMode, PendingDraw};
+
+    #[test]
+    fn reorder_over_texture_slots() {
+        // Never deleted, as there is no context to delete them from
+        let textures = (0..MAX_TEXTURE_SLOTS as u32 + 4).map(|i| unsafe { Texture::from_raw_parts(i + 1, uvec2(1, 1)) }.clone_core()).collect::<Vec<_>>();
+
+        // Side by side draws with a different texture each, as [pos, slot] vertices
+        let pending = textures.iter().enumerate().map(|(i, tex)| {
+            let x = i as f32;
+            let verts: Box<[f32]> = Box::new([x, 0.0, 0.0, x + 0.5, 0.0, 0.0, x, 0.5, 0.0]);
+            let state = BatchState::new(Material::invalid(), Box::new([2, 1]), vec![tex.clone()], BlendingMode::AlphaMix, false, false, true, MaskMode::Disabled, None);
+            return PendingDraw { key: 0.0, bounds: internal::Bounds::from_verts(&verts, &[2, 1], false), verts, tris: Box::new([0, 1, 2]), state };
+        }).collect::<Vec<_>>();
+
+        let naive_count = internal::submission_batch_count(&pending);
+        let buckets = internal::reorder(pending);
+        assert_eq!(naive_count, 2);
+        assert_eq!(buckets.len(), 2);
+
+        std::mem::forget(textures);
     }
 }
\ No newline at end of file
@@ -1,8 +1,10 @@
+use std::sync::RwLock;
+
 use gl::types as gltyp;
 
 use crate::assert_expr;
 
-use super::super::gl_call;
+use super::{gl_bindings::buffer::{GlBuffer, GlBufferKind, GlBufferUsage}, gl_call};
 
 /*pub struct GlBuffer {
     id: gltyp::GLuint,
@@ -63,4 +65,78 @@ impl Drop for GlVAO {
     fn drop(&mut self) {
         gl_call!(gl::DeleteVertexArrays(1, &self.id));
     }
-}
\ No newline at end of file
+}
+
+
+/// A VAO with its VBO and EBO, refilled every time it's used.
+pub struct StreamBuffers {
+    pub vao: GlVAO,
+    pub vbo: GlBuffer,
+    pub ebo: GlBuffer,
+}
+
+impl StreamBuffers {
+    pub fn new() -> Self {
+        let vao = GlVAO::new();
+        vao.bind();
+
+        let vbo = GlBuffer::prealloc(0, GlBufferKind::VBO, GlBufferUsage::StreamDraw);
+        let ebo = GlBuffer::prealloc(0, GlBufferKind::EBO, GlBufferUsage::StreamDraw);
+
+        return Self { vao, vbo, ebo };
+    }
+
+    pub fn upload(&mut self, verts: &[f32], tris: &[u32]) {
+        self.vao.bind();
+        self.vbo.stream(verts);
+        self.ebo.stream(tris);
+    }
+
+    pub fn bind(&self) {
+        self.vao.bind();
+        self.vbo.bind();
+        self.ebo.bind();
+    }
+}
+
+
+/// Keeps the buffers of already rendered batches so they can be reused by the next ones.
+pub struct BufferPool {
+    free: Vec<StreamBuffers>,
+}
+
+impl BufferPool {
+    pub const fn new() -> Self {
+        return Self { free: Vec::new() };
+    }
+
+    pub fn take(&mut self) -> StreamBuffers {
+        return self.free.pop().unwrap_or_else(StreamBuffers::new);
+    }
+
+    pub fn recycle(&mut self, buffers: StreamBuffers) {
+        self.free.push(buffers);
+    }
+}
+
+
+static UNIT_QUAD: RwLock<Option<(GlBuffer, GlBuffer)>> = RwLock::new(None);
+
+/// Binds the shared `[0, 1]` quad used by instanced draws and sets its single attrib at location 0.<br>
+/// Returns the number of indices to draw.
+pub fn bind_unit_quad() -> i32 {
+    const VERTS: [f32; 8] = [0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0];
+    const TRIS: [u32; 6] = [0, 1, 2, 2, 3, 0];
+
+    let mut writer = UNIT_QUAD.write().unwrap();
+    let (vbo, ebo) = writer.get_or_insert_with(|| (
+        GlBuffer::new(&VERTS, GlBufferKind::VBO, GlBufferUsage::StaticDraw),
+        GlBuffer::new(&TRIS, GlBufferKind::EBO, GlBufferUsage::StaticDraw),
+    ));
+
+    vbo.bind();
+    ebo.bind();
+    super::verts::set_vertex_attribs(&[2]);
+
+    return TRIS.len() as i32;
+}
@@ -0,0 +1,314 @@
+use crate::{assert_expr, color::Color4, math::{ivec2, lerp, mat3, uvec2, vec2, Rect}};
+
+use super::{pipeline::ScreenRect, CamData, Graphics};
+
+/// Camera with zoom, rotation, dead-zone follow, bounds clamping, smoothing and trauma based shake.<br>
+/// - Call `follow` and `update` once per frame, then `apply` to make it the camera of the active render scope.
+#[derive(Debug, Clone)]
+pub struct Camera2D {
+    /// Half of the visible area at zoom 1.
+    pub half_size: vec2,
+    /// Values over 1 zoom in.
+    pub zoom: f32,
+    /// Rotation in radians.
+    pub rot: f32,
+    /// Half extents of the area around the camera where the followed target can move without the camera moving.
+    pub dead_zone: vec2,
+    /// Area the view is kept inside of. If the view is bigger than the bounds it's centered on them.
+    pub bounds: Option<Rect>,
+    /// Time in seconds the camera takes to get most of the way to its goal, 0 for no smoothing.
+    pub smoothing: f32,
+    /// Offset in world units at full trauma.
+    pub max_shake_offset: vec2,
+    /// Rotation in radians at full trauma.
+    pub max_shake_rot: f32,
+    /// Speed of the shake noise.
+    pub shake_frequency: f32,
+    /// Trauma removed per second.
+    pub trauma_decay: f32,
+
+    pos: vec2,
+    goal: vec2,
+    trauma: f32,
+    time: f32,
+    shake_offset: vec2,
+    shake_rot: f32,
+}
+
+impl Camera2D {
+    pub fn new(pos: vec2, half_size: vec2) -> Self {
+        assert_expr!(half_size.0 != 0.0 && half_size.1 != 0.0, "The size of the camera must be a vector with non-zero components.");
+
+        return Self {
+            half_size, zoom: 1.0, rot: 0.0, dead_zone: vec2::ZERO, bounds: None, smoothing: 0.0,
+            max_shake_offset: vec2(0.3, 0.3), max_shake_rot: 0.05, shake_frequency: 15.0, trauma_decay: 1.0,
+            pos, goal: pos, trauma: 0.0, time: 0.0, shake_offset: vec2::ZERO, shake_rot: 0.0,
+        };
+    }
+
+    /// Returns the position of the camera, without shake.
+    pub fn pos(&self) -> vec2 {
+        self.pos
+    }
+
+    /// Returns the position the camera is moving towards.
+    pub fn goal(&self) -> vec2 {
+        self.goal
+    }
+
+    /// Moves the camera instantly, skipping smoothing.
+    pub fn teleport(&mut self, pos: vec2) {
+        self.pos = pos;
+        self.goal = pos;
+    }
+
+    /// Moves the goal of the camera just enough for `target` to be inside of the dead zone.
+    pub fn follow(&mut self, target: vec2) {
+        let delta = target - self.goal;
+        self.goal = self.goal + vec2(internal::outside_dead_zone(delta.0, self.dead_zone.0), internal::outside_dead_zone(delta.1, self.dead_zone.1));
+    }
+
+    /// Adds trauma, which is clamped to `[0, 1]`. The shake grows with the square of the trauma.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Half of the visible area, with zoom applied.
+    pub fn visible_half_size(&self) -> vec2 {
+        return self.half_size * (1.0 / self.zoom);
+    }
+
+    /// Advances smoothing and shake by `ts` seconds.
+    pub fn update(&mut self, ts: f32) {
+        assert_expr!(self.zoom > 0.0, "Zoom must be positive.");
+
+        self.goal = self.clamp_to_bounds(self.goal);
+        self.pos = if self.smoothing > 0.0 {
+            lerp(self.pos, self.goal, 1.0 - (-ts / self.smoothing).exp())
+        } else {
+            self.goal
+        };
+        self.pos = self.clamp_to_bounds(self.pos);
+
+        self.time += ts;
+        self.trauma = (self.trauma - self.trauma_decay * ts).max(0.0);
+
+        let shake = self.trauma * self.trauma;
+        let t = self.time * self.shake_frequency;
+        self.shake_offset = vec2(internal::noise(0, t), internal::noise(1, t)).scale(self.max_shake_offset) * shake;
+        self.shake_rot = internal::noise(2, t) * self.max_shake_rot * shake;
+    }
+
+    /// Returns the camera parameters, with zoom and shake applied.
+    pub fn cam_data(&self) -> CamData {
+        return CamData { pos: self.pos + self.shake_offset, half_size: self.visible_half_size(), rot: self.rot + self.shake_rot };
+    }
+
+    /// Returns the camera matrix, which maps world positions into clip space.
+    pub fn matrix(&self) -> mat3 {
+        let data = self.cam_data();
+        return mat3::cam_matrix_ext(data.pos, data.half_size, data.rot);
+    }
+
+    /// Makes this the camera of the active render scope.
+    /// - Same rules as `Graphics::set_cam` apply.
+    pub fn apply(&self) {
+        Graphics::set_cam_data(self.cam_data());
+    }
+
+    /// Converts a position in pixels, with the origin at the top left, to world space.
+    pub fn screen_to_world(&self, screen_pos: vec2, screen_res: uvec2) -> vec2 {
+        return screen_to_world(&self.matrix(), screen_pos, screen_res);
+    }
+
+    /// Converts a world position to pixels, with the origin at the top left.
+    pub fn world_to_screen(&self, world_pos: vec2, screen_res: uvec2) -> vec2 {
+        return world_to_screen(&self.matrix(), world_pos, screen_res);
+    }
+
+    /// Returns a view that renders through this camera into `rect`.
+    pub fn view(&self, rect: Rect) -> View {
+        return View::new(self.cam_data(), rect);
+    }
+
+    fn clamp_to_bounds(&self, pos: vec2) -> vec2 {
+        let Some(bounds) = self.bounds else { return pos };
+
+        // Extents of the rotated view
+        let (s, c) = self.rot.sin_cos();
+        let half = self.visible_half_size();
+        let extents = vec2(c.abs() * half.0 + s.abs() * half.1, s.abs() * half.0 + c.abs() * half.1);
+
+        let (min, max) = internal::min_max(bounds);
+        return vec2(internal::clamp_axis(pos.0, min.0, max.0, extents.0), internal::clamp_axis(pos.1, min.1, max.1, extents.1));
+    }
+}
+
+/// A camera that renders the scene into part of the render target. Used for split-screen and minimaps.<br>
+/// - Every view renders the same submitted geometry, nothing is drawn twice on the CPU side.
+/// - The camera isn't adjusted to the aspect ratio of the view, `half_size` should match it.
+#[derive(Debug, Clone, Copy)]
+pub struct View {
+    cam: CamData,
+    rect: Rect,
+    clear_col: Option<Color4>,
+}
+
+impl View {
+    /// `rect` is the area of the target the view renders into, normalized to `[0, 1]` with the origin at the top left.
+    pub fn new(cam: CamData, rect: Rect) -> Self {
+        assert_expr!(cam.half_size.0 != 0.0 && cam.half_size.1 != 0.0, "The size of the camera must be a vector with non-zero components.");
+        return Self { cam, rect, clear_col: None };
+    }
+
+    /// Clears the area of the view before rendering into it. By default, the view is drawn on top of what's already there.
+    pub fn clear_col(mut self, clear_col: Option<Color4>) -> Self {
+        self.clear_col = clear_col;
+        return self;
+    }
+
+    pub fn cam(&self) -> CamData {
+        self.cam
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn get_clear_col(&self) -> Option<Color4> {
+        self.clear_col
+    }
+
+    /// Returns if `screen_pos`, in pixels with the origin at the top left, lies inside of the view.
+    pub fn contains(&self, screen_pos: vec2, screen_res: uvec2) -> bool {
+        let local = self.local_pos(screen_pos, screen_res);
+        return (0.0..=1.0).contains(&local.0) && (0.0..=1.0).contains(&local.1);
+    }
+
+    /// Returns the area of the view in framebuffer pixels.
+    pub(super) fn screen_rect(&self, res: uvec2) -> ScreenRect {
+        let (min, max) = internal::min_max(self.rect);
+        let (l, r) = ((min.0 * res.0 as f32).round() as i32, (max.0 * res.0 as f32).round() as i32);
+        let (d, u) = (((1.0 - max.1) * res.1 as f32).round() as i32, ((1.0 - min.1) * res.1 as f32).round() as i32);
+
+        return ScreenRect::new(ivec2(l, d), ivec2(r - l, u - d));
+    }
+
+    /// Position inside of the view, normalized to `[0, 1]` with the origin at the top left.
+    pub(super) fn local_pos(&self, screen_pos: vec2, screen_res: uvec2) -> vec2 {
+        let (min, max) = internal::min_max(self.rect);
+        let norm = vec2(screen_pos.0 / screen_res.0 as f32, screen_pos.1 / screen_res.1 as f32);
+        return vec2((norm.0 - min.0) / (max.0 - min.0), (norm.1 - min.1) / (max.1 - min.1));
+    }
+}
+
+/// Converts a position in pixels, with the origin at the top left, to world space.
+pub(super) fn screen_to_world(cam_mat: &mat3, screen_pos: vec2, screen_res: uvec2) -> vec2 {
+    return normalized_to_world(cam_mat, vec2(screen_pos.0 / screen_res.0 as f32, screen_pos.1 / screen_res.1 as f32));
+}
+
+/// Converts a position normalized to `[0, 1]`, with the origin at the top left, to world space.
+pub(super) fn normalized_to_world(cam_mat: &mat3, pos: vec2) -> vec2 {
+    let ndc = vec2(pos.0 * 2.0 - 1.0, 1.0 - pos.1 * 2.0);
+    let stored = &cam_mat.inverse() * ndc;
+
+    // Vertices are stored with the y axis flipped
+    return vec2(stored.0, -stored.1);
+}
+
+/// Converts a world position to pixels, with the origin at the top left.
+pub(super) fn world_to_screen(cam_mat: &mat3, world_pos: vec2, screen_res: uvec2) -> vec2 {
+    let ndc = cam_mat * vec2(world_pos.0, -world_pos.1);
+    return vec2((ndc.0 * 0.5 + 0.5) * screen_res.0 as f32, (0.5 - ndc.1 * 0.5) * screen_res.1 as f32);
+}
+
+
+mod internal {
+    use crate::math::{vec2, Rect};
+
+    pub fn min_max(rect: Rect) -> (vec2, vec2) {
+        let min = vec2(rect.start.0.min(rect.end.0), rect.start.1.min(rect.end.1));
+        let max = vec2(rect.start.0.max(rect.end.0), rect.start.1.max(rect.end.1));
+        return (min, max);
+    }
+
+    pub fn outside_dead_zone(delta: f32, dead_zone: f32) -> f32 {
+        if delta > dead_zone {
+            return delta - dead_zone;
+        } else if delta < -dead_zone {
+            return delta + dead_zone;
+        } else {
+            return 0.0;
+        }
+    }
+
+    pub fn clamp_axis(pos: f32, min: f32, max: f32, extent: f32) -> f32 {
+        if max - min < extent * 2.0 {
+            return (min + max) * 0.5;
+        }
+
+        return pos.clamp(min + extent, max - extent);
+    }
+
+    /// Smooth value noise in `[-1, 1]`.
+    pub fn noise(seed: u32, t: f32) -> f32 {
+        let i = t.floor();
+        let f = t - i;
+        let f = f * f * (3.0 - 2.0 * f);
+
+        let (a, b) = (hash(seed, i as i32), hash(seed, i as i32 + 1));
+        return a + (b - a) * f;
+    }
+
+    fn hash(seed: u32, x: i32) -> f32 {
+        let mut h = (x as u32).wrapping_mul(0x27d4eb2d) ^ seed.wrapping_mul(0x165667b1);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x85ebca6b);
+        h ^= h >> 13;
+        return (h as f32 / u32::MAX as f32) * 2.0 - 1.0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{graphics::CamData, math::{mat3, uvec2, vec2, Rect}};
+
+    use super::{normalized_to_world, screen_to_world, world_to_screen, View};
+
+    #[test]
+    fn screen_world_roundtrip() {
+        let cam = mat3::cam_matrix_ext(vec2(3.0, -2.0), vec2(4.0, 2.25), 0.7);
+        let res = uvec2(1280, 720);
+
+        let center = world_to_screen(&cam, vec2(3.0, -2.0), res);
+        assert!((center.0 - 640.0).abs() < 1e-3 && (center.1 - 360.0).abs() < 1e-3);
+
+        let world = screen_to_world(&cam, vec2(100.0, 500.0), res);
+        let screen = world_to_screen(&cam, world, res);
+        assert!((screen.0 - 100.0).abs() < 1e-2 && (screen.1 - 500.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn view_screen_world_roundtrip() {
+        let view = View::new(CamData::new(vec2(3.0, -2.0), vec2(2.0, 2.25), 0.7), Rect { start: vec2(0.5, 0.0), end: vec2(1.0, 1.0) });
+        let cam = mat3::cam_matrix_ext(vec2(3.0, -2.0), vec2(2.0, 2.25), 0.7);
+        let res = uvec2(1280, 720);
+
+        // The right half of the target, with the origin at the bottom left
+        let rect = view.screen_rect(res);
+        assert_eq!((rect.l(), rect.r(), rect.d(), rect.u()), (640, 1280, 0, 720));
+
+        let center = view.local_pos(vec2(960.0, 360.0), res);
+        assert!((center.0 - 0.5).abs() < 1e-6 && (center.1 - 0.5).abs() < 1e-6);
+        assert!(!view.contains(vec2(100.0, 360.0), res));
+
+        // Project into the view, then back from the full target
+        let local = world_to_screen(&cam, vec2(4.0, -1.0), uvec2(640, 720));
+        let world = normalized_to_world(&cam, view.local_pos(local + vec2(640.0, 0.0), res));
+        assert!((world.0 - 4.0).abs() < 1e-3 && (world.1 + 1.0).abs() < 1e-3);
+    }
+}
\ No newline at end of file
@@ -1 +1,22 @@
-pub const UV_RECT_EPSILON: f32 = 0.000000;
\ No newline at end of file
+pub const UV_RECT_EPSILON: f32 = 0.000000;
+
+/// Maximum number of textures a single batch can bind at the same time.
+pub const MAX_TEXTURE_SLOTS: usize = 16;
+
+/// Maximum number of color attachments of a render texture, the minimum guaranteed by OpenGL 3.3.
+pub const MAX_COLOR_ATTACHMENTS: usize = 8;
+
+/// Binding point of the per-frame `Globals` uniform block, updated before rendering each scene.<br>
+/// Any shader can access it by declaring:
+/// ```glsl
+/// layout (std140) uniform Globals {
+///     mat3 g_Cam;
+///     vec2 g_Resolution;
+///     float g_Time;
+///     float g_DeltaTime;
+/// };
+/// ```
+pub const GLOBAL_BLOCK_BINDING: u32 = 0;
+
+/// Name of the per-frame uniform block.
+pub const GLOBAL_BLOCK_NAME: &str = "Globals";
\ No newline at end of file
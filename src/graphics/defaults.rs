@@ -2,16 +2,20 @@ use std::sync::RwLock;
 
 use crate::{Res, unwrap_res};
 
-use super::{shader::{SubShader, Shader, ShaderError, SubShaderType}, material::Material};
+use super::{consts::MAX_TEXTURE_SLOTS, pipeline::ToneMapping, preprocessor::BuiltinProvider, shader::{SubShader, Shader, ShaderError, SubShaderType}, material::Material, uniforms::Uniform};
 
 const DEF_PLAIN_VERT: &str = include_str!("../inline/def_plain_shader.vert");
 const DEF_UV_VERT: &str = include_str!("../inline/def_uv_shader.vert");
 const DEF_BLIT_VERT: &str = include_str!("../inline/def_blit_shader.vert");
+const DEF_INSTANCED_VERT: &str = include_str!("../inline/def_instanced_shader.vert");
+const DEF_MULTI_TEX_VERT: &str = include_str!("../inline/def_multi_tex_shader.vert");
 
 const DEF_PLAIN_FRAG: &str = include_str!("../inline/def_plain_shader.frag");
 const DEF_TEX_FRAG: &str = include_str!("../inline/def_tex_shader.frag");
 const DEF_ELLIPSE_FRAG: &str = include_str!("../inline/def_ellipse_shader.frag");
 const DEF_BLIT_FRAG: &str = include_str!("../inline/def_blit_shader.frag");
+const DEF_MULTI_TEX_FRAG: &str = include_str!("../inline/def_multi_tex_shader.frag");
+const DEF_TONEMAP_FRAG: &str = include_str!("../inline/def_tonemap_shader.frag");
 
 static SHADERS: RwLock<DefaultShaders> = RwLock::new(DefaultShaders::invalid());
 
@@ -19,24 +23,31 @@ pub struct DefaultShaders {
     def_plain_vert: SubShader,
     def_uv_vert: SubShader,
     def_blit_vert: SubShader,
+    def_instanced_vert: SubShader,
+    def_multi_tex_vert: SubShader,
     
     def_plain_frag: SubShader,
     def_tex_frag: SubShader,
     def_ellipse_frag: SubShader,
     def_blit_frag: SubShader,
+    def_multi_tex_frag: SubShader,
+    def_tonemap_frag: SubShader,
 
     def_rect_shader: Shader,
     def_tex_shader: Shader,
     def_ellipse_shader: Shader,
     def_blit_shader: Shader,
+    def_instanced_shader: Shader,
+    def_multi_tex_shader: Shader,
+    def_tonemap_shader: Shader,
 }
 
 impl DefaultShaders {
     const fn invalid() -> Self {
         return Self {
-            def_plain_vert: SubShader::invalid(), def_uv_vert: SubShader::invalid(), def_blit_vert: SubShader::invalid(),
-            def_plain_frag: SubShader::invalid(), def_tex_frag: SubShader::invalid(), def_ellipse_frag: SubShader::invalid(), def_blit_frag: SubShader::invalid(),
-            def_rect_shader: Shader::invalid(), def_tex_shader: Shader::invalid(), def_ellipse_shader: Shader::invalid(), def_blit_shader: Shader::invalid() };
+            def_plain_vert: SubShader::invalid(), def_uv_vert: SubShader::invalid(), def_blit_vert: SubShader::invalid(), def_instanced_vert: SubShader::invalid(), def_multi_tex_vert: SubShader::invalid(),
+            def_plain_frag: SubShader::invalid(), def_tex_frag: SubShader::invalid(), def_ellipse_frag: SubShader::invalid(), def_blit_frag: SubShader::invalid(), def_multi_tex_frag: SubShader::invalid(), def_tonemap_frag: SubShader::invalid(),
+            def_rect_shader: Shader::invalid(), def_tex_shader: Shader::invalid(), def_ellipse_shader: Shader::invalid(), def_blit_shader: Shader::invalid(), def_instanced_shader: Shader::invalid(), def_multi_tex_shader: Shader::invalid(), def_tonemap_shader: Shader::invalid() };
     }
 
     fn new() -> Res<Self, ShaderError> {
@@ -46,14 +57,25 @@ impl DefaultShaders {
         let def_tex_frag = SubShader::new(&DEF_TEX_FRAG, SubShaderType::Frag)?;
         let def_ellipse_frag = SubShader::new(&DEF_ELLIPSE_FRAG, SubShaderType::Frag)?;
         let def_blit_vert = SubShader::new(&DEF_BLIT_VERT, SubShaderType::Vert)?;
-        let def_blit_frag = SubShader::new(&DEF_BLIT_FRAG, SubShaderType::Frag)?;
+        let def_blit_frag = SubShader::preprocessed(&DEF_BLIT_FRAG, SubShaderType::Frag, &[], &BuiltinProvider)?;
+        let def_instanced_vert = SubShader::new(&DEF_INSTANCED_VERT, SubShaderType::Vert)?;
+        let def_multi_tex_vert = SubShader::new(&DEF_MULTI_TEX_VERT, SubShaderType::Vert)?;
+        let def_multi_tex_frag = SubShader::new(&DEF_MULTI_TEX_FRAG, SubShaderType::Frag)?;
+        let def_tonemap_frag = SubShader::preprocessed(&DEF_TONEMAP_FRAG, SubShaderType::Frag, &[], &BuiltinProvider)?;
         
         let def_rect_shader = Shader::new(&def_plain_vert, &def_plain_frag)?;
         let def_tex_shader = Shader::new(&def_uv_vert, &def_tex_frag)?;
         let def_ellipse_shader = Shader::new(&def_uv_vert, &def_ellipse_frag)?;
         let def_blit_shader = Shader::new(&def_blit_vert, &def_blit_frag)?;
-
-        return Ok(Self { def_plain_vert, def_plain_frag, def_uv_vert, def_tex_frag, def_ellipse_frag, def_rect_shader, def_tex_shader, def_ellipse_shader, def_blit_vert, def_blit_frag, def_blit_shader });
+        let def_instanced_shader = Shader::new(&def_instanced_vert, &def_tex_frag)?;
+        let def_multi_tex_shader = Shader::new(&def_multi_tex_vert, &def_multi_tex_frag)?;
+        let def_tonemap_shader = Shader::new(&def_blit_vert, &def_tonemap_frag)?;
+
+        return Ok(Self {
+            def_plain_vert, def_plain_frag, def_uv_vert, def_tex_frag, def_ellipse_frag, def_rect_shader, def_tex_shader, def_ellipse_shader, def_blit_vert, def_blit_frag, def_blit_shader,
+            def_instanced_vert, def_instanced_shader, def_multi_tex_vert, def_multi_tex_frag, def_multi_tex_shader,
+            def_tonemap_frag, def_tonemap_shader,
+        });
     }
 
     pub(super) fn init() {
@@ -81,6 +103,18 @@ impl DefaultShaders {
     /// Frag subshader with `uv` input.
     pub fn def_blit_frag() -> SubShader { SHADERS.read().unwrap().def_blit_frag.clone() }
 
+    /// Vert subshader with a `[corner]` vertex layout and a `[origin, axis_x, axis_y, uv0, uv1, rgba]` instance layout.
+    pub fn def_instanced_vert() -> SubShader { SHADERS.read().unwrap().def_instanced_vert.clone() }
+
+    /// Vert subshader with `[xy, rgba, uv, slot]` layout.
+    pub fn def_multi_tex_vert() -> SubShader { SHADERS.read().unwrap().def_multi_tex_vert.clone() }
+
+    /// Frag subshader with `rgba`, `uv` and `slot` input. Output color is the texture bound to `slot`.
+    pub fn def_multi_tex_frag() -> SubShader { SHADERS.read().unwrap().def_multi_tex_frag.clone() }
+
+    /// Frag subshader with `uv` input. Output color is the tone mapped texture, driven by the `exposure` and `tonemap_op` uniforms.
+    pub fn def_tonemap_frag() -> SubShader { SHADERS.read().unwrap().def_tonemap_frag.clone() }
+
     /// Shader for rects and lines. `plain_vert` + `plain_frag`.
     pub fn def_rect_shader() -> Shader { SHADERS.read().unwrap().def_rect_shader.clone() }
 
@@ -92,6 +126,15 @@ impl DefaultShaders {
 
     /// Shader for blit. `blit_vert` + `blit_frag`.
     pub fn def_blit_shader() -> Shader { SHADERS.read().unwrap().def_blit_shader.clone() }
+
+    /// Shader for instanced textured rects. `instanced_vert` + `tex_frag`.
+    pub fn def_instanced_shader() -> Shader { SHADERS.read().unwrap().def_instanced_shader.clone() }
+
+    /// Shader for multi-textured rects. `multi_tex_vert` + `multi_tex_frag`.
+    pub fn def_multi_tex_shader() -> Shader { SHADERS.read().unwrap().def_multi_tex_shader.clone() }
+
+    /// Shader for tone mapping blits. `blit_vert` + `tonemap_frag`.
+    pub fn def_tonemap_shader() -> Shader { SHADERS.read().unwrap().def_tonemap_shader.clone() }
 }
 
 
@@ -103,12 +146,19 @@ pub struct DefaultMaterials {
     def_tex_material: Material,
     def_ellipse_material: Material,
     def_blit_material: Material,
+    def_instanced_material: Material,
+    def_multi_tex_material: Material,
+    def_reinhard_material: Material,
+    def_aces_material: Material,
+    /// Location of `exposure` in the tone mapping materials.
+    tonemap_exposure_address: i32,
 }
 
 impl DefaultMaterials {
     const fn invalid() -> Self {
         return Self {
-            def_rect_material: Material::invalid(), def_tex_material: Material::invalid(), def_ellipse_material: Material::invalid(), def_blit_material: Material::invalid(), def_line_material: Material::invalid(),
+            def_rect_material: Material::invalid(), def_tex_material: Material::invalid(), def_ellipse_material: Material::invalid(), def_blit_material: Material::invalid(), def_line_material: Material::invalid(), def_instanced_material: Material::invalid(), def_multi_tex_material: Material::invalid(),
+            def_reinhard_material: Material::invalid(), def_aces_material: Material::invalid(), tonemap_exposure_address: -1,
         };
     }
 
@@ -124,8 +174,20 @@ impl DefaultMaterials {
         let def_tex_material = Material::new(&shaders.def_tex_shader, &[]);
         let def_ellipse_material = Material::new(&shaders.def_ellipse_shader, &[]);
         let def_blit_material = Material::new(&shaders.def_blit_shader, &[]);
+        let def_instanced_material = Material::new(&shaders.def_instanced_shader, &[]);
 
-        return Self { def_rect_material, def_tex_material, def_ellipse_material, def_blit_material, def_line_material };
+        let slot_names = (0..MAX_TEXTURE_SLOTS).map(|i| format!("textures[{i}]\0").into_bytes()).collect::<Vec<_>>();
+        let slot_uniforms = slot_names.iter().enumerate().map(|(i, x)| (x.as_slice(), Uniform::Int(i as i32))).collect::<Vec<_>>();
+        let def_multi_tex_material = Material::new(&shaders.def_multi_tex_shader, &slot_uniforms);
+
+        let def_reinhard_material = Material::new(&shaders.def_tonemap_shader, &[(b"exposure\0", Uniform::Float(1.0)), (b"tonemap_op\0", Uniform::Int(0))]);
+        let def_aces_material = Material::new(&shaders.def_tonemap_shader, &[(b"exposure\0", Uniform::Float(1.0)), (b"tonemap_op\0", Uniform::Int(1))]);
+        let tonemap_exposure_address = def_reinhard_material.get_uniform_address(b"exposure\0").unwrap_or(-1);
+
+        return Self {
+            def_rect_material, def_tex_material, def_ellipse_material, def_blit_material, def_line_material, def_instanced_material, def_multi_tex_material,
+            def_reinhard_material, def_aces_material, tonemap_exposure_address,
+        };
     }
 
     
@@ -134,4 +196,21 @@ impl DefaultMaterials {
     pub fn def_tex_material() -> Material { MATERIALS.read().unwrap().def_tex_material.clone() }
     pub fn def_ellipse_material() -> Material { MATERIALS.read().unwrap().def_ellipse_material.clone() }
     pub fn def_blit_material() -> Material { MATERIALS.read().unwrap().def_blit_material.clone() }
+    pub fn def_instanced_material() -> Material { MATERIALS.read().unwrap().def_instanced_material.clone() }
+    pub fn def_multi_tex_material() -> Material { MATERIALS.read().unwrap().def_multi_tex_material.clone() }
+
+    /// Returns a tone mapping blit material. Exposure can be changed later through the `exposure` uniform.
+    pub fn tonemap_material(mapping: ToneMapping, exposure: f32) -> Material {
+        let reader = MATERIALS.read().unwrap();
+        let mut material = match mapping {
+            ToneMapping::Reinhard => reader.def_reinhard_material.clone(),
+            ToneMapping::Aces => reader.def_aces_material.clone(),
+        };
+
+        if reader.tonemap_exposure_address != -1 {
+            material.set_uniform(reader.tonemap_exposure_address, Uniform::Float(exposure));
+        }
+
+        return material;
+    }
 }
\ No newline at end of file
@@ -0,0 +1,207 @@
+use std::fmt::Display;
+
+use super::{preprocessor::LineOrigin, shader::SubShaderType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single entry of a shader compilation or linking log.
+#[derive(Debug, Clone)]
+pub struct ShaderDiagnostic {
+    /// `None` for linking diagnostics.
+    pub stage: Option<SubShaderType>,
+    pub severity: Severity,
+    /// Line (starting at 1) of the compiled source, if the driver reported it.
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub message: String,
+    /// Where the line comes from before preprocessing.
+    pub origin: Option<LineOrigin>,
+    /// Offending line of source.
+    pub source_line: Option<String>,
+}
+
+impl Display for ShaderDiagnostic {
+    /// Pretty prints the diagnostic, pointing at the offending source line.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        writeln!(f, "{severity}: {}", self.message)?;
+
+        let stage = match self.stage {
+            Some(x) => format!("{x} shader"),
+            None => "linker".into(),
+        };
+
+        let location = match &self.origin {
+            Some(LineOrigin::Main(line)) => format!("{line}"),
+            Some(LineOrigin::Include { path, line }) => format!("{path}:{line}"),
+            Some(LineOrigin::Injected) => "<injected>".into(),
+            None => return Ok(()),
+        };
+        let location = match self.column {
+            Some(col) => format!("{location}:{col}"),
+            None => location,
+        };
+        writeln!(f, "  --> {stage}, {location}")?;
+
+        if let (Some(src), Some(line)) = (&self.source_line, self.origin_line()) {
+            let num = line.to_string();
+            let pad = " ".repeat(num.len());
+
+            writeln!(f, " {pad} |")?;
+            writeln!(f, " {num} | {src}")?;
+            if let Some(col) = self.column.filter(|x| *x > 0) {
+                writeln!(f, " {pad} | {}^", " ".repeat(col as usize - 1))?;
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+impl ShaderDiagnostic {
+    fn origin_line(&self) -> Option<u32> {
+        return match &self.origin {
+            Some(LineOrigin::Main(line)) | Some(LineOrigin::Include { line, .. }) => Some(*line),
+            _ => None,
+        };
+    }
+}
+
+/// Parses a driver info log.<br>
+/// - Understands the Mesa (`0:12(5): error: ...`), NVIDIA (`0(12) : error C0000: ...`) and AMD/Intel (`ERROR: 0:12: ...`) formats.
+/// - Lines that can't be parsed are appended to the previous diagnostic.
+pub fn parse_log(log: &str, stage: Option<SubShaderType>, src: Option<&str>) -> Vec<ShaderDiagnostic> {
+    let mut res: Vec<ShaderDiagnostic> = Vec::new();
+
+    for line in log.lines().map(|x| x.trim_end_matches('\0').trim()).filter(|x| !x.is_empty()) {
+        match internal::parse_line(line) {
+            Some(entry) => {
+                let source_line = entry.line.and_then(|l| src?.lines().nth(l.checked_sub(1)? as usize)).map(|x| x.to_string());
+
+                res.push(ShaderDiagnostic {
+                    stage,
+                    severity: entry.severity,
+                    line: entry.line,
+                    column: entry.column,
+                    message: entry.message.into(),
+                    origin: entry.line.map(LineOrigin::Main),
+                    source_line,
+                });
+            },
+            None => match res.last_mut() {
+                Some(last) => {
+                    last.message.push('\n');
+                    last.message.push_str(line);
+                },
+                None => res.push(ShaderDiagnostic { stage, severity: Severity::Error, line: None, column: None, message: line.into(), origin: None, source_line: None }),
+            },
+        }
+    }
+
+    return res;
+}
+
+/// Maps the lines of the diagnostics back through a preprocessed line table.
+pub fn map_lines(diagnostics: &mut [ShaderDiagnostic], lines: &[LineOrigin]) {
+    for d in diagnostics {
+        d.origin = d.line.and_then(|l| lines.get(l.checked_sub(1)? as usize)).cloned();
+    }
+}
+
+/// Pretty prints every diagnostic, falling back to `raw` if there are none.
+pub fn pretty(diagnostics: &[ShaderDiagnostic], raw: &str) -> String {
+    if diagnostics.is_empty() {
+        return raw.trim_end_matches('\0').into();
+    }
+
+    return diagnostics.iter().map(|x| x.to_string()).collect::<Vec<_>>().join("\n");
+}
+
+
+mod internal {
+    use super::Severity;
+
+    pub struct Entry<'a> {
+        pub severity: Severity,
+        pub line: Option<u32>,
+        pub column: Option<u32>,
+        pub message: &'a str,
+    }
+
+    pub fn parse_line(line: &str) -> Option<Entry<'_>> {
+        // AMD / Intel: "ERROR: 0:12: message"
+        for (prefix, severity) in [("ERROR:", Severity::Error), ("WARNING:", Severity::Warning)] {
+            if let Some(rest) = line.strip_prefix(prefix) {
+                let (_, rest) = take_num(rest.trim_start())?;
+                let (l, rest) = take_num(rest.strip_prefix(':')?)?;
+                let message = rest.strip_prefix(':')?.trim();
+                return Some(Entry { severity, line: Some(l), column: None, message });
+            }
+        }
+
+        let (_, rest) = take_num(line)?;
+        let (l, column, rest) = if let Some(rest) = rest.strip_prefix(':') {
+            // Mesa: "0:12(5): error: message"
+            let (l, rest) = take_num(rest)?;
+            match rest.strip_prefix('(') {
+                Some(rest) => {
+                    let (col, rest) = take_num(rest)?;
+                    (l, Some(col), rest.strip_prefix(')')?)
+                },
+                None => (l, None, rest),
+            }
+        } else {
+            // NVIDIA: "0(12) : error C0000: message"
+            let (l, rest) = take_num(rest.strip_prefix('(')?)?;
+            (l, None, rest.strip_prefix(')')?)
+        };
+
+        let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+        let (severity, rest) = if let Some(rest) = rest.strip_prefix("error") {
+            (Severity::Error, rest)
+        } else if let Some(rest) = rest.strip_prefix("warning") {
+            (Severity::Warning, rest)
+        } else {
+            (Severity::Error, rest)
+        };
+
+        // Skip vendor error codes
+        let message = match rest.find(':') {
+            Some(i) if rest[..i].trim().chars().all(|x| x.is_ascii_alphanumeric()) => &rest[i + 1..],
+            _ => rest,
+        };
+
+        return Some(Entry { severity, line: Some(l), column, message: message.trim() });
+    }
+
+    fn take_num(src: &str) -> Option<(u32, &str)> {
+        let end = src.find(|x: char| !x.is_ascii_digit()).unwrap_or(src.len());
+        let num = src[..end].parse().ok()?;
+        return Some((num, &src[end..]));
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::{internal::parse_line, Severity};
+
+    #[test]
+    fn driver_formats() {
+        let mesa = parse_line("0:12(5): error: `x' undeclared").unwrap();
+        assert_eq!((mesa.line, mesa.column, mesa.severity, mesa.message), (Some(12), Some(5), Severity::Error, "`x' undeclared"));
+
+        let nvidia = parse_line("0(7) : warning C7533: global variable gl_FragColor is deprecated").unwrap();
+        assert_eq!((nvidia.line, nvidia.column, nvidia.severity, nvidia.message), (Some(7), None, Severity::Warning, "global variable gl_FragColor is deprecated"));
+
+        let amd = parse_line("ERROR: 0:3: 'y' : undeclared identifier").unwrap();
+        assert_eq!((amd.line, amd.severity, amd.message), (Some(3), Severity::Error, "'y' : undeclared identifier"));
+    }
+}
\ No newline at end of file
@@ -31,13 +31,15 @@ pub fn integer_scaling_mouse_pos(screen_pos: vec2, src_res: uvec2, dst_res: uvec
     }
 }
 
+/// Converts a screen position to world space with the camera of the active render scope.
+/// - Takes camera rotation and snapping into account.
 pub fn screen_to_world_pos(screen_pos: vec2, screen_res: uvec2) -> vec2 {
-    let screen_pos = vec2(screen_pos.0, screen_res.1 as f32 - screen_pos.1); // Invert y-axis
-    let pos_clip = screen_pos.inv_scale(vec2(screen_res.0 as f32, screen_res.1 as f32)) * 2.0 - vec2::ONE;
+    return Graphics::screen_to_world(screen_pos, screen_res);
+}
 
-    let cam_data = Graphics::get_cam_data();
-    
-    return pos_clip.scale(cam_data.half_size) + cam_data.pos;
+/// Converts a world position to screen space with the camera of the active render scope.
+pub fn world_to_screen_pos(world_pos: vec2, screen_res: uvec2) -> vec2 {
+    return Graphics::world_to_screen(world_pos, screen_res);
 }
 
 mod integer_scaling {
@@ -40,22 +40,25 @@ impl Into<gl_enum> for GlBufferUsage {
 pub struct GlBuffer {
     id: gl_uint,
     kind: gl_enum,
+    capacity: usize,
 }
 
 impl GlBuffer {
     #[allow(unused)]
     pub fn prealloc(size: usize, kind: GlBufferKind, usage: GlBufferUsage) -> Self {
-        let buf = Self::empty(kind);
+        let mut buf = Self::empty(kind);
         let usage = usage.into();
 
         gl_call!(gl::BindBuffer(buf.kind, buf.id));
         gl_call!(gl::BufferData(buf.kind, size as isize, std::ptr::null(), usage));
+        buf.capacity = size;
 
         return buf;
     }
 
+    #[allow(unused)]
     pub fn new<T>(data: &[T], kind: GlBufferKind, usage: GlBufferUsage) -> Self {
-        let buf = Self::empty(kind);
+        let mut buf = Self::empty(kind);
         buf.set(data, usage);
         return buf;
     }
@@ -67,14 +70,29 @@ impl GlBuffer {
         gl_call!(gl::GenBuffers(1, &mut id));
         assert_expr!(id != 0);
 
-        return Self { id, kind };
+        return Self { id, kind, capacity: 0 };
     }
 
-    pub fn set<T>(&self, data: &[T], usage: GlBufferUsage) {
+    pub fn set<T>(&mut self, data: &[T], usage: GlBufferUsage) {
         let usage = usage.into();
+        let size = std::mem::size_of::<T>() * data.len();
 
         gl_call!(gl::BindBuffer(self.kind, self.id));
-        gl_call!(gl::BufferData(self.kind, (std::mem::size_of::<T>() * data.len()) as isize, data.as_ptr() as *const std::ffi::c_void, usage));
+        gl_call!(gl::BufferData(self.kind, size as isize, data.as_ptr() as *const std::ffi::c_void, usage));
+        self.capacity = size;
+    }
+
+    /// Uploads the data reusing the allocated storage when it's big enough, growing it otherwise.<br>
+    /// The previous storage is orphaned, so the driver doesn't have to wait for pending draws.
+    pub fn stream<T>(&mut self, data: &[T]) {
+        let size = std::mem::size_of::<T>() * data.len();
+        if size > self.capacity {
+            self.capacity = size.next_power_of_two();
+        }
+
+        gl_call!(gl::BindBuffer(self.kind, self.id));
+        gl_call!(gl::BufferData(self.kind, self.capacity as isize, std::ptr::null(), GlBufferUsage::StreamDraw.into()));
+        gl_call!(gl::BufferSubData(self.kind, 0, size as isize, data.as_ptr() as *const std::ffi::c_void));
     }
 
     #[allow(unused)]
@@ -86,6 +104,11 @@ impl GlBuffer {
     pub fn bind(&self) {
         gl_call!(gl::BindBuffer(self.kind, self.id));
     }
+
+    /// Binds the buffer to an indexed binding point, used by uniform blocks.
+    pub fn bind_base(&self, index: gl_uint) {
+        gl_call!(gl::BindBufferBase(self.kind, index, self.id));
+    }
 }
 
 impl Drop for GlBuffer {
@@ -11,16 +11,73 @@ pub fn gl_enable_blend() {
     gl_call!(gl::Enable(gl::BLEND));
 }
 
-pub enum GlBlendingMode {
-    AlphaMix,
-    Additive,
-    Multiplicative,
+pub struct GlBlendingMode {
+    pub src: gl_enum,
+    pub dst: gl_enum,
+    pub op: gl_enum,
+    pub alpha_src: gl_enum,
+    pub alpha_dst: gl_enum,
+    pub alpha_op: gl_enum,
 }
 
 pub fn gl_set_blend(mode: GlBlendingMode) {
+    gl_call!(gl::BlendFuncSeparate(mode.src, mode.dst, mode.alpha_src, mode.alpha_dst));
+    gl_call!(gl::BlendEquationSeparate(mode.op, mode.alpha_op));
+}
+
+pub fn gl_set_scissor(rect: Option<(i32, i32, i32, i32)>) {
+    match rect {
+        Some((x, y, w, h)) => {
+            gl_call!(gl::Enable(gl::SCISSOR_TEST));
+            gl_call!(gl::Scissor(x, y, w, h));
+        },
+        None => gl_call!(gl::Disable(gl::SCISSOR_TEST)),
+    }
+}
+
+/// Runs `f` with the given pack alignment, restoring the previous one afterwards.
+pub fn gl_with_pack_alignment<T>(alignment: i32, f: impl FnOnce() -> T) -> T {
+    let mut prev = 0;
+    gl_call!(gl::GetIntegerv(gl::PACK_ALIGNMENT, &mut prev));
+    gl_call!(gl::PixelStorei(gl::PACK_ALIGNMENT, alignment));
+
+    let res = f();
+
+    gl_call!(gl::PixelStorei(gl::PACK_ALIGNMENT, prev));
+    return res;
+}
+
+pub enum GlStencilMode {
+    Disabled,
+    Write,
+    Test,
+}
+
+pub fn gl_set_stencil(mode: GlStencilMode) {
     match mode {
-        GlBlendingMode::AlphaMix => gl_call!(gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA)), 
-        GlBlendingMode::Additive => gl_call!(gl::BlendFunc(gl::SRC_ALPHA, gl::ONE)),
-        GlBlendingMode::Multiplicative => gl_call!(gl::BlendFunc(gl::DST_COLOR, gl::ZERO)),
+        GlStencilMode::Disabled => {
+            gl_call!(gl::Disable(gl::STENCIL_TEST));
+            gl_call!(gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE));
+        },
+        GlStencilMode::Write => {
+            gl_call!(gl::Enable(gl::STENCIL_TEST));
+            gl_call!(gl::StencilFunc(gl::ALWAYS, 1, 0xFF));
+            gl_call!(gl::StencilOp(gl::KEEP, gl::KEEP, gl::REPLACE));
+            gl_call!(gl::StencilMask(0xFF));
+            gl_call!(gl::ColorMask(gl::FALSE, gl::FALSE, gl::FALSE, gl::FALSE));
+        },
+        GlStencilMode::Test => {
+            gl_call!(gl::Enable(gl::STENCIL_TEST));
+            gl_call!(gl::StencilFunc(gl::EQUAL, 1, 0xFF));
+            gl_call!(gl::StencilOp(gl::KEEP, gl::KEEP, gl::KEEP));
+            gl_call!(gl::StencilMask(0x00));
+            gl_call!(gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE));
+        },
     }
 }
+
+pub fn gl_clear_stencil() {
+    gl_call!(gl::StencilMask(0xFF));
+    gl_call!(gl::ClearStencil(0));
+    gl_call!(gl::Clear(gl::STENCIL_BUFFER_BIT));
+}
@@ -1,9 +1,15 @@
-use crate::{graphics::{gl_bindings::gl, shader::ShaderError}, Res};
+use std::sync::{atomic::{AtomicU32, Ordering}, Arc, RwLock};
+
+use crate::{graphics::{diagnostics, gl_bindings::gl, reflection::ShaderVariable, shader::ShaderError}, Res};
 
 use super::{gl::gl_call, gl_uint, shader::GlShader};
 
+#[derive(Debug)]
 pub struct GlProgram {
-    id: gl_uint,
+    id: AtomicU32,
+    generation: AtomicU32,
+    /// Active uniforms, reflected the first time they are requested.
+    uniforms: RwLock<Option<Arc<[ShaderVariable]>>>,
 }
 
 impl GlProgram {
@@ -14,28 +20,58 @@ impl GlProgram {
         gl_call!(gl::LinkProgram(id));
 
         let mut success = 0;
-        let mut info_log = [0u8; 512];
         gl_call!(gl::GetProgramiv(id, gl::LINK_STATUS, &mut success));
         if success == 0 {
-            gl_call!(gl::GetProgramInfoLog(id, 512, std::ptr::null_mut(), info_log.as_mut_ptr() as *mut i8));
-            let str_form = std::str::from_utf8(&info_log).unwrap();
-            return Err(ShaderError::LinkingError { msg: str_form.into() });
+            let mut log_len = 0;
+            gl_call!(gl::GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut log_len));
+
+            let mut info_log = vec![0u8; log_len.max(1) as usize];
+            gl_call!(gl::GetProgramInfoLog(id, info_log.len() as i32, std::ptr::null_mut(), info_log.as_mut_ptr() as *mut i8));
+            gl_call!(gl::DeleteProgram(id));
+
+            let msg = String::from_utf8_lossy(&info_log).trim_end_matches('\0').to_string();
+            let diagnostics = diagnostics::parse_log(&msg, None, None);
+            return Err(ShaderError::LinkingError { msg, diagnostics });
         }
 
-        return Ok(Self { id } );
+        return Ok(Self { id: AtomicU32::new(id), generation: AtomicU32::new(0), uniforms: RwLock::new(None) } );
     }
 
     pub fn id(&self) -> gl_uint {
-        self.id
+        self.id.load(Ordering::Relaxed)
+    }
+
+    /// Number of times the program has been replaced.
+    pub fn generation(&self) -> u32 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Takes the program of `other`, the current one is deleted.
+    pub fn replace(&self, other: GlProgram) {
+        let old = self.id.swap(other.id(), Ordering::Relaxed);
+        other.id.store(old, Ordering::Relaxed);
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        *self.uniforms.write().unwrap() = None;
+    }
+
+    /// Returns the active uniforms, calling `reflect` with the program id if they weren't reflected since the program was created or replaced.
+    pub fn uniforms(&self, reflect: impl FnOnce(gl_uint) -> Vec<ShaderVariable>) -> Arc<[ShaderVariable]> {
+        if let Some(x) = self.uniforms.read().unwrap().as_ref() {
+            return x.clone();
+        }
+
+        let uniforms: Arc<[ShaderVariable]> = reflect(self.id()).into();
+        *self.uniforms.write().unwrap() = Some(uniforms.clone());
+        return uniforms;
     }
 
     pub fn enable(&self) {
-        gl_call!(gl::UseProgram(self.id));
+        gl_call!(gl::UseProgram(self.id()));
     }
 }
 
 impl Drop for GlProgram {
     fn drop(&mut self) {
-        gl_call!(gl::DeleteProgram(self.id));
+        gl_call!(gl::DeleteProgram(self.id()));
     }
 }
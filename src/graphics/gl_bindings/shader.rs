@@ -1,6 +1,6 @@
 use std::ffi::CString;
 
-use crate::{graphics::{gl_bindings::gl, shader::{ShaderError, SubShaderType}}, Res};
+use crate::{graphics::{diagnostics, gl_bindings::gl, shader::{ShaderError, SubShaderType}}, Res};
 
 use super::{gl::gl_call, gl_uint};
 
@@ -22,20 +22,26 @@ pub struct GlShader {
 }
 
 impl GlShader {
-    pub fn new(src: &str, kind: GlShaderType) -> Res<Self, ShaderError> {
-        let id = gl_call!(gl::CreateShader(kind as u32));
+    pub fn new(src: &str, kind: SubShaderType) -> Res<Self, ShaderError> {
+        let id = gl_call!(gl::CreateShader(GlShaderType::from(kind) as u32));
 
-        let src = CString::new(src).map_err(|e| ShaderError::from(e))?;
-        gl_call!(gl::ShaderSource(id, 1, &src.as_ptr(), std::ptr::null()));
+        let c_src = CString::new(src).map_err(|e| ShaderError::from(e))?;
+        gl_call!(gl::ShaderSource(id, 1, &c_src.as_ptr(), std::ptr::null()));
         gl_call!(gl::CompileShader(id));
 
         let mut success = 0;
-        let mut info_log = [0u8; 512];
         gl_call!(gl::GetShaderiv(id, gl::COMPILE_STATUS, &mut success));
         if success == 0 {
-            gl_call!(gl::GetShaderInfoLog(id, 512, std::ptr::null_mut(), info_log.as_mut_ptr() as *mut i8));
-            let str_form = std::str::from_utf8(&info_log).unwrap();
-            return Err(ShaderError::CompilationError { kind, msg: str_form.into() } );
+            let mut log_len = 0;
+            gl_call!(gl::GetShaderiv(id, gl::INFO_LOG_LENGTH, &mut log_len));
+
+            let mut info_log = vec![0u8; log_len.max(1) as usize];
+            gl_call!(gl::GetShaderInfoLog(id, info_log.len() as i32, std::ptr::null_mut(), info_log.as_mut_ptr() as *mut i8));
+            gl_call!(gl::DeleteShader(id));
+
+            let msg = String::from_utf8_lossy(&info_log).trim_end_matches('\0').to_string();
+            let diagnostics = diagnostics::parse_log(&msg, Some(kind), Some(src));
+            return Err(ShaderError::CompilationError { kind, msg, diagnostics } );
         }
 
         return Ok(Self { id });
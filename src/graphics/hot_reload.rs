@@ -0,0 +1,127 @@
+use std::{path::{Path, PathBuf}, sync::{RwLock, Weak}, time::{Instant, SystemTime}};
+
+use crate::{log_error, log_info, Res};
+
+use super::{gl_bindings::program::GlProgram, shader::{Shader, ShaderError, SubShaderType}};
+
+const POLL_INTERVAL: f32 = 0.25;
+
+static HOT_RELOAD: RwLock<HotReloadData> = RwLock::new(HotReloadData::new());
+
+struct HotReloadData {
+    enabled: bool,
+    last_poll: Option<Instant>,
+    watched: Vec<WatchedShader>,
+}
+
+struct WatchedShader {
+    program: Weak<GlProgram>,
+    vert_path: PathBuf,
+    frag_path: PathBuf,
+    /// Every file the shader depends on, with its last modification time.
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl HotReloadData {
+    const fn new() -> Self {
+        Self { enabled: false, last_poll: None, watched: Vec::new() }
+    }
+}
+
+pub(super) fn set_enabled(enabled: bool) {
+    HOT_RELOAD.write().unwrap().enabled = enabled;
+}
+
+pub(super) fn is_enabled() -> bool {
+    return HOT_RELOAD.read().unwrap().enabled;
+}
+
+/// Compiles a shader from disk, resolving includes relative to each file.<br>
+/// Returns the shader and the files it depends on.
+pub(super) fn compile(vert_path: &Path, frag_path: &Path) -> Res<(Shader, Vec<PathBuf>), ShaderError> {
+    let mut files = vec![vert_path.to_path_buf(), frag_path.to_path_buf()];
+
+    let vert = internal::compile_sub(vert_path, SubShaderType::Vert, &mut files)?;
+    let frag = internal::compile_sub(frag_path, SubShaderType::Frag, &mut files)?;
+
+    return Ok((Shader::new(&vert, &frag)?, files));
+}
+
+/// Starts watching the files of a shader created with `Shader::from_files`.
+pub(super) fn watch(shader: &Shader, vert_path: &Path, frag_path: &Path, files: Vec<PathBuf>) {
+    let files = files.into_iter().map(|x| { let time = internal::modified(&x); (x, time) }).collect();
+
+    HOT_RELOAD.write().unwrap().watched.push(WatchedShader {
+        program: shader.downgrade(),
+        vert_path: vert_path.to_path_buf(),
+        frag_path: frag_path.to_path_buf(),
+        files,
+    });
+}
+
+/// Recompiles the watched shaders whose files changed.<br>
+/// - On success, the program is swapped in place, so every material using it is updated.
+/// - On failure, the previous program is kept.
+pub(super) fn poll() {
+    let mut writer = HOT_RELOAD.write().unwrap();
+    if !writer.enabled || writer.last_poll.is_some_and(|x| x.elapsed().as_secs_f32() < POLL_INTERVAL) {
+        return;
+    }
+    writer.last_poll = Some(Instant::now());
+
+    writer.watched.retain(|x| x.program.strong_count() != 0);
+    for watched in &mut writer.watched {
+        let mut changed = false;
+        for (path, time) in &mut watched.files {
+            let new_time = internal::modified(path);
+            if new_time != *time {
+                *time = new_time;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            continue;
+        }
+
+        let Some(program) = watched.program.upgrade() else { continue };
+        match compile(&watched.vert_path, &watched.frag_path) {
+            Ok((shader, files)) => {
+                program.replace(shader.into_program());
+                watched.files = files.into_iter().map(|x| { let time = internal::modified(&x); (x, time) }).collect();
+
+                log_info!("Shader '{}' + '{}' reloaded.", watched.vert_path.display(), watched.frag_path.display());
+            },
+            Err(e) => log_error!("Couldn't reload shader '{}' + '{}':\n{e}", watched.vert_path.display(), watched.frag_path.display()),
+        }
+    }
+}
+
+
+mod internal {
+    use std::{path::{Path, PathBuf}, time::SystemTime};
+
+    use crate::{graphics::{preprocessor::{preprocess, FileProvider, LineOrigin}, shader::{ShaderError, SubShader, SubShaderType}}, Res};
+
+    /// Includes are resolved relative to `path`, and nested ones relative to the file that contains them.
+    pub fn compile_sub(path: &Path, kind: SubShaderType, files: &mut Vec<PathBuf>) -> Res<SubShader, ShaderError> {
+        let src = std::fs::read_to_string(path)?;
+        let root = path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        let processed = preprocess(&src, &[], &FileProvider::new(&root))?;
+        for origin in &processed.lines {
+            if let LineOrigin::Include { path, .. } = origin {
+                let include = root.join(path);
+                if include.exists() && !files.contains(&include) {
+                    files.push(include);
+                }
+            }
+        }
+
+        return SubShader::new(&processed.source, kind).map_err(|e| e.map_lines(&processed.lines));
+    }
+
+    pub fn modified(path: &Path) -> Option<SystemTime> {
+        return std::fs::metadata(path).and_then(|x| x.modified()).ok();
+    }
+}
\ No newline at end of file
@@ -1,16 +1,23 @@
+use std::sync::atomic::{AtomicI32, AtomicU32, Ordering};
+
 use crate::{log_warn, assert_expr};
 
-use super::{shader::Shader, uniforms::Uniform, gl_call};
+use super::{reflection, shader::Shader, uniforms::Uniform, gl_call};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug)]
 pub struct Material {
     shader: Shader,
-    uniforms: Vec<(gl::types::GLint, Uniform)>
+    /// Locations are resolved again from `names` when the shader is hot reloaded.
+    uniforms: Vec<(AtomicI32, Uniform)>,
+    /// Zero terminated names of `uniforms`, used to find them again after the shader is hot reloaded.
+    names: Vec<Box<[u8]>>,
+    /// Shader generation the locations of `uniforms` were resolved for.
+    generation: AtomicU32,
 }
 
 impl Material {
     pub(super) const fn invalid() -> Self {
-        Self { shader: Shader::invalid(), uniforms: Vec::new() }
+        Self { shader: Shader::invalid(), uniforms: Vec::new(), names: Vec::new(), generation: AtomicU32::new(0) }
     }
     
     pub fn new(shader: &Shader, uniforms: &[(&[u8], Uniform)]) -> Self {
@@ -21,10 +28,13 @@ impl Material {
                 log_warn!("Uniform '{}' was not present for the provided shader. Value will be skipped", std::str::from_utf8(k).unwrap());
                 return None;
             }
-            return Some((id, v.clone()));
+            reflection::validate_uniform(shader, std::str::from_utf8(&k[..k.len() - 1]).unwrap_or_default(), id, v);
+
+            return Some(((AtomicI32::new(id), v.clone()), Box::<[u8]>::from(*k)));
         }).collect::<Vec<_>>();
+        let (uniforms, names) = uniforms.into_iter().unzip();
 
-        return Self { shader: shader.clone(), uniforms };
+        return Self { shader: shader.clone(), uniforms, names, generation: AtomicU32::new(shader.generation()) };
     }
 
     pub fn set_uniform_by_name(&mut self, name: &[u8], value: Uniform) {
@@ -36,20 +46,13 @@ impl Material {
             },
         };
 
-        self.set_uniform(id, value);
+        self.set_uniform_internal(id, value, Some(name));
     }
 
+    /// Sets the value of the uniform at `address`.
+    /// - Uniforms first set through an address that isn't the base location of a uniform, like an element of an array, are lost when the shader is hot reloaded. Use `set_uniform_by_name` for those.
     pub fn set_uniform(&mut self, address: i32, value: Uniform) {
-        assert_expr!(address >= 0, "Address must be postive.");
-
-        match self.uniforms.iter().position(|x| x.0 == address) {
-            Some(i) => {
-                self.uniforms[i].1 = value;
-            },
-            None => {
-                self.uniforms.push((address, value));
-            },
-        };
+        self.set_uniform_internal(address, value, None);
     }
 
     pub fn get_uniform_address(&self, name: &[u8]) -> Option<i32> {
@@ -63,23 +66,93 @@ impl Material {
         }
     }
 
-    pub(super) fn enable(&self) {
+    /// Enables the shader and uploads the uniforms.
+    /// - Texture uniforms are bound to consecutive slots starting at `first_tex_slot`.
+    pub(super) fn enable(&self, first_tex_slot: u8) {
         self.shader.enable();
+        self.refresh_locations();
 
+        let mut tex_slot = first_tex_slot;
         for (l, u) in &self.uniforms {
+            let l = l.load(Ordering::Relaxed);
+            if l == -1 {
+                continue;
+            }
+
             match u {
-                Uniform::Float(x) => gl_call!(gl::Uniform1f(*l, *x)),
-                Uniform::Float2(x, y) => gl_call!(gl::Uniform2f(*l, *x, *y)),
-                Uniform::Float3(x, y, z) => gl_call!(gl::Uniform3f(*l, *x, *y, *z)),
-                Uniform::Float4(x, y, z, w) => gl_call!(gl::Uniform4f(*l, *x, *y, *z ,*w)),
-                Uniform::Int(x) => gl_call!(gl::Uniform1i(*l, *x)),
-                Uniform::Uint(x) => gl_call!(gl::Uniform1ui(*l, *x)),
-                //Uniform::Texture(x) => gl_call!(gl::Uniform1ui(*l, x.core().id())),
+                Uniform::Texture(x) => {
+                    x.core().enable(tex_slot);
+                    gl_call!(gl::Uniform1i(l, tex_slot as i32));
+                    tex_slot += 1;
+                },
+                _ => u.upload(l),
             }
         }
     }
 
+    /// Returns the number of texture uniforms.
+    pub(super) fn texture_count(&self) -> usize {
+        return self.uniforms.iter().filter(|x| matches!(x.1, Uniform::Texture(_))).count();
+    }
+
     pub fn shader(&self) -> &Shader {
         &self.shader
     }
+
+    fn set_uniform_internal(&mut self, address: i32, value: Uniform, name: Option<&[u8]>) {
+        assert_expr!(address >= 0, "Address must be postive.");
+        self.refresh_locations();
+
+        match self.uniforms.iter().position(|x| x.0.load(Ordering::Relaxed) == address) {
+            Some(i) => {
+                self.uniforms[i].1 = value;
+            },
+            None => {
+                // Element locations, like the one of `arr[2]`, aren't reported by reflection
+                let var = self.shader.cached_uniforms().iter().find(|x| x.location == address).cloned();
+                if let Some(var) = &var {
+                    reflection::validate_uniform(&self.shader, &var.name, address, &value);
+                }
+
+                let name = match (name, var) {
+                    (Some(name), _) => Box::<[u8]>::from(name),
+                    (None, Some(var)) => format!("{}\0", var.name).into_bytes().into_boxed_slice(),
+                    (None, None) => Box::<[u8]>::from(&b"\0"[..]),
+                };
+
+                self.uniforms.push((AtomicI32::new(address), value));
+                self.names.push(name);
+            },
+        };
+    }
+
+    /// Resolves the uniform locations again if the shader was hot reloaded since they were last resolved.
+    fn refresh_locations(&self) {
+        let generation = self.shader.generation();
+        if self.generation.load(Ordering::Relaxed) == generation {
+            return;
+        }
+
+        for ((l, _), name) in self.uniforms.iter().zip(&self.names) {
+            l.store(gl_call!(gl::GetUniformLocation(self.shader.id(), name.as_ptr() as *const i8)), Ordering::Relaxed);
+        }
+        self.generation.store(generation, Ordering::Relaxed);
+    }
+}
+
+impl Clone for Material {
+    fn clone(&self) -> Self {
+        let uniforms = self.uniforms.iter().map(|(l, u)| (AtomicI32::new(l.load(Ordering::Relaxed)), u.clone())).collect();
+        return Self { shader: self.shader.clone(), uniforms, names: self.names.clone(), generation: AtomicU32::new(self.generation.load(Ordering::Relaxed)) };
+    }
+}
+
+/// Uniforms are compared by name and value, so copies made before and after a hot reload still match.
+/// - Uniforms without a name are compared by location.
+impl PartialEq for Material {
+    fn eq(&self, other: &Self) -> bool {
+        return self.shader == other.shader && self.names == other.names && self.uniforms.iter().zip(&other.uniforms).zip(&self.names).all(|((a, b), name)| {
+            a.1 == b.1 && (name.len() > 1 || a.0.load(Ordering::Relaxed) == b.0.load(Ordering::Relaxed))
+        });
+    }
 }
\ No newline at end of file
@@ -4,7 +4,7 @@ use gl_bindings::{gl_enable_blend, gl_set_blend, GlBlendingMode};
 
 use crate::{assert_expr, color::{Color, Color4}, graphics::defaults::{DefaultMaterials, DefaultShaders}, log_info, math::{mat3, quad::Quad, uvec2, vec2, Rect}, window::Window};
 
-use self::{material::Material, pipeline::{RenderPipeline, RenderTexture}, render_scope::{RenderScope, Snapping}, texture::{Sprite, Texture}, ui::{text::{SourcedFromGraphics, Text}, UI}};
+use self::{batch::ClipRect, camera::View, material::Material, uniforms::GlobalBlock, polyline::{LineCap, LineJoin, LineStyle}, pipeline::{RenderPipeline, RenderTexture, RenderTexturePool}, render_scope::{RenderScope, Snapping}, texture::{Sprite, Texture}, ui::{text::{SourcedFromGraphics, Text}, UI}};
 
 use super::gl_call;
 
@@ -12,9 +12,16 @@ use super::gl_call;
 pub mod render_scope;
 pub mod material;
 pub mod shader;
+pub mod preprocessor;
+pub mod diagnostics;
+pub mod reflection;
 pub mod texture;
 pub mod uniforms;
 pub mod pipeline;
+pub mod post;
+pub mod render_graph;
+pub mod camera;
+pub mod polyline;
 pub mod gfx;
 pub mod defaults;
 pub mod ui;
@@ -24,6 +31,7 @@ mod buffers;
 mod verts;
 mod batch;
 mod gl_bindings;
+mod hot_reload;
 
 
 static GRAPHICS: RwLock<Graphics> = RwLock::new(Graphics::new());
@@ -36,6 +44,10 @@ pub enum Mode {
     Textured,
     Ellipse,
     Custom,
+    /// Textures drawn while instancing is enabled.
+    Instanced,
+    /// Textures drawn while multi-texturing is enabled.
+    MultiTextured,
 }
 
 impl Mode {
@@ -49,15 +61,21 @@ impl Mode {
 }
 
 
-const DEFAULT_CAM_DATA: CamData = CamData { pos: vec2::ZERO, half_size: vec2::ONE };
+const DEFAULT_CAM_DATA: CamData = CamData { pos: vec2::ZERO, half_size: vec2::ONE, rot: 0.0 };
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
 pub struct CamData {
     pos: vec2,
     half_size: vec2,
+    rot: f32,
 }
 
 impl CamData {
+    /// - `half_size` must not have any axis be zero.
+    pub fn new(pos: vec2, half_size: vec2, rot: f32) -> Self {
+        return Self { pos, half_size, rot };
+    }
+
     pub fn pos(&self) -> vec2 {
         self.pos
     }
@@ -65,6 +83,11 @@ impl CamData {
     pub fn half_size(&self) -> vec2 {
         self.half_size
     }
+
+    /// Rotation in radians.
+    pub fn rot(&self) -> f32 {
+        self.rot
+    }
 }
 
 
@@ -83,6 +106,7 @@ impl Graphics {
     pub(crate) fn init() {
         DefaultShaders::init();
         DefaultMaterials::init();
+        GlobalBlock::init();
 
         gl_enable_blend(); 
         Self::set_blending_mode(BlendingMode::AlphaMix);
@@ -197,6 +221,19 @@ impl Graphics {
         GRAPHICS.write().unwrap().active_scope.draw_line(from, to, colors);
     }
 
+    /// Draws a thick line through `points`, tessellated into triangles.<br>
+    /// - `width` is in world units.
+    /// - `colors` must have either one color for the whole line or one color per point.
+    pub fn draw_polyline(points: &[vec2], width: f32, join: LineJoin, cap: LineCap, colors: &[Color4]) {
+        Self::draw_polyline_ext(points, &LineStyle::new(width, join, cap), colors);
+    }
+
+    /// Draws a thick line through `points`, which can also be closed or dashed.<br>
+    /// - `colors` must have either one color for the whole line or one color per point.
+    pub fn draw_polyline_ext(points: &[vec2], style: &LineStyle, colors: &[Color4]) {
+        GRAPHICS.write().unwrap().active_scope.draw_polyline(points, style, colors);
+    }
+
 
     /// Draw a custom mesh. Prone to not behaving. Not affected by pivot.
     pub unsafe fn draw_custom_mesh(pos: vec2, rot: f32, scale: vec2, vert_data: &[f32], tri_data: &[u32], vert_attribs: &[usize], textures: &[&Texture]) {
@@ -227,6 +264,31 @@ impl Graphics {
         GRAPHICS.write().unwrap().active_scope.set_material(material, mode);
     }
 
+    /// Enables or disables instanced texture drawing.<br>
+    /// - While enabled, textures and sprites with a single color are sent as one instance instead of a full quad.
+    /// - Instanced textures use the material of `Mode::Instanced`.
+    pub fn set_instancing(enabled: bool) {
+        GRAPHICS.write().unwrap().active_scope.instancing = enabled;
+    }
+
+    /// Returns if instanced texture drawing is enabled.
+    pub fn is_instancing() -> bool {
+        return GRAPHICS.read().unwrap().active_scope.instancing;
+    }
+
+    /// Enables or disables multi-textured drawing.<br>
+    /// - While enabled, textures and sprites are assigned to a free texture slot of the current batch, so different textures can share a draw call.
+    /// - Multi-textured draws use the material of `Mode::MultiTextured`.
+    /// - Instancing takes priority over multi-texturing.
+    pub fn set_multi_texturing(enabled: bool) {
+        GRAPHICS.write().unwrap().active_scope.multi_texturing = enabled;
+    }
+
+    /// Returns if multi-textured drawing is enabled.
+    pub fn is_multi_texturing() -> bool {
+        return GRAPHICS.read().unwrap().active_scope.multi_texturing;
+    }
+
     /// Sets the current blending mode.
     pub fn set_blending_mode(mode: BlendingMode) {
         GRAPHICS.write().unwrap().active_scope.blending = mode;
@@ -237,6 +299,74 @@ impl Graphics {
         return GRAPHICS.read().unwrap().active_scope.blending;
     }
 
+    /// Sets the current layer. Layers are drawn in ascending order, no matter the order of submission.
+    /// - Default layer is `0`.
+    pub fn set_layer(layer: i32) {
+        GRAPHICS.write().unwrap().active_scope.layer = layer;
+    }
+
+    /// Returns the current layer.
+    pub fn get_layer() -> i32 {
+        return GRAPHICS.read().unwrap().active_scope.layer;
+    }
+
+    /// Sets how draws are ordered inside a layer.
+    /// - Default is `LayerSorting::Submission`.
+    pub fn set_layer_sorting(layer: i32, sorting: LayerSorting) {
+        GRAPHICS.write().unwrap().active_scope.set_layer_sorting(layer, sorting);
+    }
+
+    /// Returns how draws are ordered inside a layer.
+    pub fn get_layer_sorting(layer: i32) -> LayerSorting {
+        return GRAPHICS.read().unwrap().active_scope.get_layer_sorting(layer);
+    }
+
+    /// Sets the sort key for the following draws. Only used by layers with `LayerSorting::Key`.
+    pub fn set_sort_key(key: f32) {
+        GRAPHICS.write().unwrap().active_scope.sort_key = key;
+    }
+
+    /// Returns the current sort key.
+    pub fn get_sort_key() -> f32 {
+        return GRAPHICS.read().unwrap().active_scope.sort_key;
+    }
+
+    /// Starts writing a new mask. Anything drawn until `end_mask` writes into the mask instead of the color buffer.<br>
+    /// - Textures with an alpha below 0.5 are discarded from the mask.
+    /// - Masks are applied in submission order, so they require `LayerSorting::Submission`. Other sortings may move the draws that write a mask after the ones clipped by it.
+    pub fn begin_mask() {
+        GRAPHICS.write().unwrap().active_scope.begin_mask();
+    }
+
+    /// Starts writing a new mask. Anything drawn until `end_mask` writes into the mask instead of the color buffer.<br>
+    /// - Textures with an alpha below `alpha_cutoff` are discarded from the mask.
+    /// - Masks are applied in submission order, so they require `LayerSorting::Submission`. Other sortings may move the draws that write a mask after the ones clipped by it.
+    pub fn begin_mask_ext(alpha_cutoff: f32) {
+        GRAPHICS.write().unwrap().active_scope.begin_mask_ext(alpha_cutoff);
+    }
+
+    /// Stops writing the mask. Anything drawn until `clear_mask` is clipped by it.
+    pub fn end_mask() {
+        GRAPHICS.write().unwrap().active_scope.end_mask();
+    }
+
+    /// Disables the mask. Following draws won't be clipped.
+    pub fn clear_mask() {
+        GRAPHICS.write().unwrap().active_scope.clear_mask();
+    }
+
+    /// Clips following draws to the bounding box of `quad`, intersected with the current clip.<br>
+    /// - Must be matched with a call to `pop_clip`.
+    pub fn push_clip(quad: Quad) {
+        let points = [quad.ld, quad.rd, quad.lu, quad.ru].map(|x| vec2(x.0, -x.1));
+        GRAPHICS.write().unwrap().active_scope.push_clip(ClipRect::from_points(&points));
+    }
+
+    /// Restores the previous clip.
+    pub fn pop_clip() {
+        GRAPHICS.write().unwrap().active_scope.pop_clip();
+    }
+
     /// Sets the current render target.
     pub fn set_render_target(target: u8) {
         GRAPHICS.write().unwrap().active_scope.render_target = target;
@@ -305,7 +435,46 @@ impl Graphics {
     /// - For the global render scope, changes will be applied the next frame.
     /// - For non global render scopes, changes will be applied on the next tick.
     pub fn set_cam(pos: vec2, half_size: vec2) {
-        GRAPHICS.write().unwrap().active_scope.set_camera(CamData { pos, half_size });
+        GRAPHICS.write().unwrap().active_scope.set_camera(CamData { pos, half_size, rot: 0.0 });
+    }
+
+    /// Sets the camera parameters, with a rotation in radians.
+    /// - Same rules as `set_cam` apply.
+    pub fn set_cam_ext(pos: vec2, half_size: vec2, rot: f32) {
+        GRAPHICS.write().unwrap().active_scope.set_camera(CamData { pos, half_size, rot });
+    }
+
+    /// Sets the camera parameters from a `CamData`, like the one returned by `Camera2D::cam_data`.
+    pub fn set_cam_data(cam_data: CamData) {
+        GRAPHICS.write().unwrap().active_scope.set_camera(cam_data);
+    }
+
+    /// Sets the views the scene is rendered through, for split-screen or minimaps. Each view renders the same geometry with its own camera.<br>
+    /// - An empty slice goes back to rendering through the camera only.
+    /// - Views are kept between frames, like the camera.
+    pub fn set_views(views: &[View]) {
+        GRAPHICS.write().unwrap().active_scope.set_views(views);
+    }
+
+    /// Returns the current views.
+    pub fn get_views() -> Vec<View> {
+        return GRAPHICS.read().unwrap().active_scope.get_views();
+    }
+
+    /// Converts a position in pixels, with the origin at the top left, to world space through the topmost view under it.<br>
+    /// Returns the index of the view and the world position.
+    pub fn view_screen_to_world(screen_pos: vec2, screen_res: uvec2) -> Option<(usize, vec2)> {
+        return GRAPHICS.read().unwrap().active_scope.view_screen_to_world(screen_pos, screen_res);
+    }
+
+    /// Converts a position in pixels, with the origin at the top left, to world space through the camera matrix.
+    pub fn screen_to_world(screen_pos: vec2, screen_res: uvec2) -> vec2 {
+        return GRAPHICS.read().unwrap().active_scope.screen_to_world(screen_pos, screen_res);
+    }
+
+    /// Converts a world position to pixels, with the origin at the top left, through the camera matrix.
+    pub fn world_to_screen(world_pos: vec2, screen_res: uvec2) -> vec2 {
+        return GRAPHICS.read().unwrap().active_scope.world_to_screen(world_pos, screen_res);
     }
 
     /// Returns the camera matrix from the current camera config.
@@ -324,10 +493,21 @@ impl Graphics {
         let reader = GRAPHICS.read().unwrap();
         assert_expr!(reader.active_scope.is_global, "The global render scope must be active at the end of the frame!");
         
+        let window = unsafe { window.as_mut().unwrap_unchecked() };
+        GlobalBlock::advance(window.ts());
+        hot_reload::poll();
+
+        if let Some(headless_rt) = window.headless_target_mut() {
+            let stats = reader.active_scope.render_internal(headless_rt, true, pipeline);
+            RenderTexturePool::end_frame();
+            return stats;
+        }
+
         let mut screen_rt = RenderTexture::to_screen(screen_res);
 
-        let stats = GRAPHICS.read().unwrap().active_scope.render_internal(&mut screen_rt, true, pipeline);
-        unsafe { window.as_mut().unwrap_unchecked() }.swap_buffers();
+        let stats = reader.active_scope.render_internal(&mut screen_rt, true, pipeline);
+        window.swap_buffers();
+        RenderTexturePool::end_frame();
         return stats;
     }
 
@@ -348,25 +528,128 @@ pub enum BlendingMode {
     Additive,
     /// Color values are multiplied with those of the background.
     Multiplicative,
+    /// Inverse of multiplying the inverted colors, always brightens the background.
+    Screen,
+    /// Color values are subtracted from the background.
+    Subtract,
+    /// Keeps the minimum of each color channel.
+    Min,
+    /// Keeps the maximum of each color channel.
+    Max,
+    /// Overlay-style blending. Mid-gray leaves the background unchanged, lighter colors brighten it and darker ones darken it.<br>
+    /// - Approximated as `2 * src * dst`, since true overlay requires reading the background in the shader.
+    Overlay,
+    /// Like `AlphaMix`, but expects colors already multiplied by their alpha.
+    PremultipliedAlpha,
+    /// Fully custom blend function and equation, with separate color and alpha settings.
+    Custom { src: BlendFactor, dst: BlendFactor, op: BlendOp, alpha_src: BlendFactor, alpha_dst: BlendFactor, alpha_op: BlendOp },
 }
 
 impl BlendingMode {
     pub(super) fn apply(&self) {
+        use BlendFactor as F;
+        use BlendOp as O;
+
+        let (src, dst, op, alpha_src, alpha_dst, alpha_op) = match *self {
+            BlendingMode::AlphaMix => (F::SrcAlpha, F::OneMinusSrcAlpha, O::Add, F::SrcAlpha, F::OneMinusSrcAlpha, O::Add),
+            BlendingMode::Additive => (F::SrcAlpha, F::One, O::Add, F::SrcAlpha, F::One, O::Add),
+            BlendingMode::Multiplicative => (F::DstColor, F::Zero, O::Add, F::DstColor, F::Zero, O::Add),
+            BlendingMode::Screen => (F::One, F::OneMinusSrcColor, O::Add, F::One, F::OneMinusSrcAlpha, O::Add),
+            BlendingMode::Subtract => (F::SrcAlpha, F::One, O::ReverseSubtract, F::Zero, F::One, O::Add),
+            BlendingMode::Min => (F::One, F::One, O::Min, F::One, F::One, O::Min),
+            BlendingMode::Max => (F::One, F::One, O::Max, F::One, F::One, O::Max),
+            BlendingMode::Overlay => (F::DstColor, F::SrcColor, O::Add, F::Zero, F::One, O::Add),
+            BlendingMode::PremultipliedAlpha => (F::One, F::OneMinusSrcAlpha, O::Add, F::One, F::OneMinusSrcAlpha, O::Add),
+            BlendingMode::Custom { src, dst, op, alpha_src, alpha_dst, alpha_op } => (src, dst, op, alpha_src, alpha_dst, alpha_op),
+        };
+
+        gl_set_blend(GlBlendingMode {
+            src: src.gl_enum(), dst: dst.gl_enum(), op: op.gl_enum(),
+            alpha_src: alpha_src.gl_enum(), alpha_dst: alpha_dst.gl_enum(), alpha_op: alpha_op.gl_enum(),
+        });
+    }
+}
+
+/// Factor applied to the source or destination in `BlendingMode::Custom`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlendFactor {
+    Zero,
+    One,
+    SrcColor,
+    OneMinusSrcColor,
+    DstColor,
+    OneMinusDstColor,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    DstAlpha,
+    OneMinusDstAlpha,
+}
+
+impl BlendFactor {
+    fn gl_enum(&self) -> gl::types::GLenum {
         match self {
-            BlendingMode::AlphaMix => gl_set_blend(GlBlendingMode::AlphaMix),
-            BlendingMode::Additive => gl_set_blend(GlBlendingMode::Additive),
-            BlendingMode::Multiplicative => gl_set_blend(GlBlendingMode::Multiplicative),
+            BlendFactor::Zero => gl::ZERO,
+            BlendFactor::One => gl::ONE,
+            BlendFactor::SrcColor => gl::SRC_COLOR,
+            BlendFactor::OneMinusSrcColor => gl::ONE_MINUS_SRC_COLOR,
+            BlendFactor::DstColor => gl::DST_COLOR,
+            BlendFactor::OneMinusDstColor => gl::ONE_MINUS_DST_COLOR,
+            BlendFactor::SrcAlpha => gl::SRC_ALPHA,
+            BlendFactor::OneMinusSrcAlpha => gl::ONE_MINUS_SRC_ALPHA,
+            BlendFactor::DstAlpha => gl::DST_ALPHA,
+            BlendFactor::OneMinusDstAlpha => gl::ONE_MINUS_DST_ALPHA,
         }
     }
 }
 
+/// Equation used to combine the source and destination in `BlendingMode::Custom`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlendOp {
+    /// `src + dst`
+    Add,
+    /// `src - dst`
+    Subtract,
+    /// `dst - src`
+    ReverseSubtract,
+    /// `min(src, dst)`, factors are ignored.
+    Min,
+    /// `max(src, dst)`, factors are ignored.
+    Max,
+}
+
+impl BlendOp {
+    fn gl_enum(&self) -> gl::types::GLenum {
+        match self {
+            BlendOp::Add => gl::FUNC_ADD,
+            BlendOp::Subtract => gl::FUNC_SUBTRACT,
+            BlendOp::ReverseSubtract => gl::FUNC_REVERSE_SUBTRACT,
+            BlendOp::Min => gl::MIN,
+            BlendOp::Max => gl::MAX,
+        }
+    }
+}
 
 
-#[derive(Debug)]
+/// Defines how draws are ordered inside a layer.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LayerSorting {
+    /// Draws are rendered in submission order.
+    Submission,
+    /// Draws are rendered by ascending sort key, set with `Graphics::set_sort_key`.
+    Key,
+    /// Draws with a lower bottom point are rendered on top. Useful for top-down games.
+    YSort,
+    /// Draws may be reordered to merge batches with the same state. Overlapping draws keep their relative order.
+    Reorder,
+}
+
+
+#[derive(Debug, Clone, Copy, Default)]
 pub struct RenderStats {
     draw_calls: usize,
     batch_draw_calls: usize,
     rt_draw_calls: usize,
+    saved_draw_calls: usize,
 }
 
 impl RenderStats {
@@ -384,4 +667,9 @@ impl RenderStats {
     pub fn rt_draw_calls(&self) -> usize {
         self.rt_draw_calls
     }
+
+    /// Returns the number of draw calls saved by layers with `LayerSorting::Reorder`.
+    pub fn saved_draw_calls(&self) -> usize {
+        self.saved_draw_calls
+    }
 }
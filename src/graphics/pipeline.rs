@@ -1,9 +1,14 @@
-use crate::{assert_expr, color::Color4, graphics::{buffers::GlVAO, gl_bindings::buffer::{GlBuffer, GlBufferKind, GlBufferUsage}, verts, DefaultMaterials}, math::{ivec2, mat3, uvec2, Rect}};
+use crate::{assert_expr, color::Color4, graphics::{buffers::StreamBuffers, consts::MAX_COLOR_ATTACHMENTS, verts, DefaultMaterials}, math::{ivec2, mat3, uvec2, Rect}, Res};
 
-use super::{gl_call, batch::TargetBatchData, RenderStats, texture::{TextureFiltering, Texture}, BlendingMode, material::Material};
+use std::{ops::{Deref, DerefMut}, path::Path, sync::RwLock};
+
+use super::{gl_call, batch::TargetBatchData, camera::View, gl_bindings::{gl_clear_stencil, gl_set_scissor, gl_with_pack_alignment}, RenderStats, texture::{Pixels, TextureError, TextureFiltering, Texture}, BlendingMode, material::Material};
 
 pub const DEFAULT_RENDER_TARGET: u8 = 0;
 
+static BLIT_BUFFERS: RwLock<Option<StreamBuffers>> = RwLock::new(None);
+static RT_POOL: RwLock<RenderTexturePool> = RwLock::new(RenderTexturePool::new());
+
 #[derive(Debug, Clone, Copy)]
 pub struct ScreenRect {
     l: i32, r: i32, u: i32, d: i32,
@@ -48,37 +53,158 @@ pub trait RenderPipeline {
     fn render(&self, screen_rt: &mut RenderTexture, scene_data: &SceneRenderData, ui_data: Option<&SceneRenderData>, stats: &mut RenderStats);
 }
 
+/// Storage format of a color attachment.<br>
+/// - Float formats keep values outside of `[0, 1]`, so they can be tone mapped later on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorFormat {
+    R8,
+    RG8,
+    RGB8,
+    RGBA8,
+    RGBA16F,
+    RGBA32F,
+    /// Packed float format without alpha, cheaper than `RGBA16F`.
+    R11G11B10F,
+}
+
+impl ColorFormat {
+    /// Internal format, format and type.
+    fn gl_format(&self) -> (gl::types::GLenum, gl::types::GLenum, gl::types::GLenum) {
+        match self {
+            Self::R8 => (gl::R8, gl::RED, gl::UNSIGNED_BYTE),
+            Self::RG8 => (gl::RG8, gl::RG, gl::UNSIGNED_BYTE),
+            Self::RGB8 => (gl::RGB8, gl::RGB, gl::UNSIGNED_BYTE),
+            Self::RGBA8 => (gl::RGBA8, gl::RGBA, gl::UNSIGNED_BYTE),
+            Self::RGBA16F => (gl::RGBA16F, gl::RGBA, gl::HALF_FLOAT),
+            Self::RGBA32F => (gl::RGBA32F, gl::RGBA, gl::FLOAT),
+            Self::R11G11B10F => (gl::R11F_G11F_B10F, gl::RGB, gl::FLOAT),
+        }
+    }
+
+    /// Returns if the format stores values outside of `[0, 1]`.
+    pub fn is_hdr(&self) -> bool {
+        return matches!(self, Self::RGBA16F | Self::RGBA32F | Self::R11G11B10F);
+    }
+}
+
+/// Operator used to map HDR colors into `[0, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToneMapping {
+    Reinhard,
+    /// Fitted ACES filmic curve.
+    Aces,
+}
+
+/// Storage format of a depth / stencil attachment.<br>
+/// - Masking requires a format with stencil.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthStencilFormat {
+    Depth16,
+    Depth24,
+    Depth32F,
+    Depth24Stencil8,
+    Depth32FStencil8,
+}
+
+impl DepthStencilFormat {
+    /// Internal format and attachment point.
+    fn gl_format(&self) -> (gl::types::GLenum, gl::types::GLenum) {
+        match self {
+            Self::Depth16 => (gl::DEPTH_COMPONENT16, gl::DEPTH_ATTACHMENT),
+            Self::Depth24 => (gl::DEPTH_COMPONENT24, gl::DEPTH_ATTACHMENT),
+            Self::Depth32F => (gl::DEPTH_COMPONENT32F, gl::DEPTH_ATTACHMENT),
+            Self::Depth24Stencil8 => (gl::DEPTH24_STENCIL8, gl::DEPTH_STENCIL_ATTACHMENT),
+            Self::Depth32FStencil8 => (gl::DEPTH32F_STENCIL8, gl::DEPTH_STENCIL_ATTACHMENT),
+        }
+    }
+}
+
+/// Declares the attachments of a render texture.<br>
+/// - Color attachment `i` is written by the frag output at `layout (location = i)`, see `nogine/frag_data.glsl`.
+/// - Attachments that the shader doesn't write to are left undefined, default shaders only write the first one.
+/// - Defaults to no color attachments, linear filtering and no depth / stencil attachment.
+/// - Render textures without a depth / stencil attachment get a `Depth24Stencil8` one the first time masks are rendered into them.
+pub struct RenderTextureBuilder {
+    res: uvec2,
+    filtering: TextureFiltering,
+    colors: Vec<ColorFormat>,
+    depth_stencil: Option<DepthStencilFormat>,
+}
+
+impl RenderTextureBuilder {
+    /// Sets the filtering of every color attachment.
+    pub fn filtering(mut self, filtering: TextureFiltering) -> Self {
+        self.filtering = filtering;
+        return self;
+    }
+
+    /// Adds a color attachment.
+    pub fn color(mut self, format: ColorFormat) -> Self {
+        self.colors.push(format);
+        return self;
+    }
+
+    /// Sets the depth / stencil attachment, `None` to have no attachment.
+    pub fn depth_stencil(mut self, format: Option<DepthStencilFormat>) -> Self {
+        self.depth_stencil = format;
+        return self;
+    }
+
+    pub fn build(self) -> RenderTexture {
+        assert_expr!(self.res.0 != 0 && self.res.1 != 0, "None of the resolution axis can be 0");
+        assert_expr!(!self.colors.is_empty(), "A render texture needs at least one color attachment");
+        assert_expr!(self.colors.len() <= MAX_COLOR_ATTACHMENTS, "A render texture can't have more than {MAX_COLOR_ATTACHMENTS} color attachments");
+
+        let mut fbo = 0;
+        gl_call!(gl::GenFramebuffers(1, &mut fbo));
+        gl_call!(gl::BindFramebuffer(gl::FRAMEBUFFER, fbo));
+
+        let textures = self.colors.iter().enumerate().map(|(i, format)| {
+            let tex = internal::create_color_tex(self.res, self.filtering, *format);
+            gl_call!(gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0 + i as u32, gl::TEXTURE_2D, tex, 0));
+            tex
+        }).collect::<Vec<_>>();
+
+        let draw_buffers = (0..textures.len() as u32).map(|i| gl::COLOR_ATTACHMENT0 + i).collect::<Vec<_>>();
+        gl_call!(gl::DrawBuffers(draw_buffers.len() as i32, draw_buffers.as_ptr()));
+
+        let stencil_rb = match self.depth_stencil {
+            Some(format) => internal::attach_depth_stencil(self.res, format),
+            None => 0,
+        };
+        gl_call!(gl::BindFramebuffer(gl::FRAMEBUFFER, 0));
+
+        let extra_col_tex = textures[1..].iter().map(|x| unsafe { Texture::from_raw_parts(*x, self.res) }).collect();
+        return RenderTexture { fbo, col_tex: textures[0], extra_col_tex, stencil_rb, res: self.res, format: self.colors[0], alpha: 1.0 };
+    }
+}
+
 pub struct RenderTexture {
     fbo: gl::types::GLuint,
     col_tex: gl::types::GLuint,
+    /// Color attachments after the first one.
+    extra_col_tex: Vec<Texture>,
+    stencil_rb: gl::types::GLuint,
     res: uvec2,
+    /// Format of the first color attachment.
+    format: ColorFormat,
     alpha: f32,
 }
 
 impl RenderTexture {
     pub(super) fn to_screen(res: uvec2) -> Self {
-        return Self { fbo: 0, col_tex: 0, res, alpha: 1.0 };
+        return Self { fbo: 0, col_tex: 0, extra_col_tex: Vec::new(), stencil_rb: 0, res, format: ColorFormat::RGBA8, alpha: 1.0 };
     }
 
+    /// Creates a render texture with a single `RGBA8` color attachment.<br>
+    /// - A `Depth24Stencil8` attachment is only added the first time masks are rendered into it.
     pub fn new(res: uvec2, filtering: TextureFiltering) -> Self {
-        assert_expr!(res.0 != 0 && res.1 != 0, "None of the resolution axis can be 0");
-        
-        let mut fbo = 0;
-        gl_call!(gl::GenFramebuffers(1, &mut fbo));
-        gl_call!(gl::BindFramebuffer(gl::FRAMEBUFFER, fbo));
-
-        let mut col_tex = 0;
-        gl_call!(gl::GenTextures(1, &mut col_tex));
-        gl_call!(gl::BindTexture(gl::TEXTURE_2D, col_tex));
-        gl_call!(gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA as i32, res.0 as i32, res.1 as i32, 0, gl::RGBA, gl::UNSIGNED_BYTE, std::ptr::null()));
-        gl_call!(gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, filtering as u32 as i32));
-        gl_call!(gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, filtering as u32 as i32));
-        gl_call!(gl::BindTexture(gl::TEXTURE_2D, 0));
-        
-        gl_call!(gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, col_tex, 0));
-        gl_call!(gl::BindFramebuffer(gl::FRAMEBUFFER, 0));
+        return Self::builder(res).filtering(filtering).color(ColorFormat::RGBA8).build();
+    }
 
-        return Self { fbo, col_tex, res, alpha: 1.0 };
+    /// Starts declaring a render texture with multiple color attachments or a custom depth / stencil attachment.
+    pub fn builder(res: uvec2) -> RenderTextureBuilder {
+        return RenderTextureBuilder { res, filtering: TextureFiltering::Linear, colors: Vec::new(), depth_stencil: None };
     }
 
     pub(super) unsafe fn new_from_existing(tex: &Texture) -> Self {
@@ -92,42 +218,74 @@ impl RenderTexture {
         gl_call!(gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, col_tex, 0));
         gl_call!(gl::BindFramebuffer(gl::FRAMEBUFFER, 0));
 
-        return Self { fbo, col_tex, res, alpha: 1.0 };
+        return Self { fbo, col_tex, extra_col_tex: Vec::new(), stencil_rb: 0, res, format: ColorFormat::RGBA8, alpha: 1.0 };
     }
 
+    /// Creates a render texture with the resolution and format of the first attachment of `rt`.
     pub fn sized_as(rt: &RenderTexture, filtering: TextureFiltering) -> Self {
-        return Self::new(rt.res, filtering);
+        return Self::builder(rt.res).filtering(filtering).color(rt.format).build();
     }
 
+    /// Renders `scene_data` through each of its views, or through its camera if it has none.
     pub fn render_scene(&mut self, scene_data: &SceneRenderData, target: u8, stats: &mut RenderStats) {
-        gl_call!(gl::Viewport(0, 0, self.res.0 as i32, self.res.1 as i32));
-        
-        RenderTexture::bind(self);
+        if scene_data.views.is_empty() {
+            self.render_scene_ext(scene_data, target, ScreenRect::new(ivec2::ZERO, ivec2(self.res.0 as i32, self.res.1 as i32)), stats);
+            return;
+        }
 
-        if let Some(products) = scene_data.products.iter().find(|x| x.0 == target).map(|x| &x.1) {
-            for b in &products.render_batches {
-                b.render(scene_data.cam);
+        for (view, cam) in scene_data.views {
+            let rect = view.screen_rect(self.res);
+            if let Some(clear_col) = view.get_clear_col() {
+                self.clear_ext(clear_col, rect);
             }
-            stats.draw_calls += products.render_batches.len();
-            stats.batch_draw_calls += products.render_batches.len();
+
+            internal::render_batches(self, scene_data.products, cam, rect, target, stats);
         }
+    }
 
-        RenderTexture::unbind();
+    /// Renders `scene_data` into `rect` through its camera, ignoring its views.
+    pub fn render_scene_ext(&mut self, scene_data: &SceneRenderData, target: u8, rect: ScreenRect, stats: &mut RenderStats) {
+        internal::render_batches(self, scene_data.products, scene_data.cam, rect, target, stats);
     }
 
     pub fn clear(&mut self, color: Color4) {
         RenderTexture::bind(self);
 
         gl_call!(gl::ClearColor(color.0, color.1, color.2, color.3));
-        gl_call!(gl::Clear(gl::COLOR_BUFFER_BIT));
+        gl_call!(gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT));
+        gl_clear_stencil();
 
         RenderTexture::unbind();
     }
 
+    /// Clears only the area inside of `rect`.
+    pub fn clear_ext(&mut self, color: Color4, rect: ScreenRect) {
+        RenderTexture::bind(self);
+        gl_set_scissor(Some((rect.l, rect.d, rect.r - rect.l, rect.u - rect.d)));
+
+        gl_call!(gl::ClearColor(color.0, color.1, color.2, color.3));
+        gl_call!(gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT));
+        gl_clear_stencil();
+
+        gl_set_scissor(None);
+        RenderTexture::unbind();
+    }
+
     pub fn downscaled(&self, factor: u32, target_filtering: TextureFiltering, stats: &mut RenderStats) -> Self {
         assert_expr!(factor != 0, "Scaling factor cannot be 0");
         
-        let mut target_rt = RenderTexture::new(uvec2((self.res.0 / factor).max(1), (self.res.1 / factor).max(1)), target_filtering);
+        let mut target_rt = RenderTexture::builder(uvec2((self.res.0 / factor).max(1), (self.res.1 / factor).max(1))).filtering(target_filtering).color(self.format).build();
+        target_rt.clear(Color4::CLEAR);
+        target_rt.render_with_shader(&self, &DefaultMaterials::def_blit_material(), BlendingMode::AlphaMix, stats);
+
+        return target_rt;
+    }
+
+    /// Like `downscaled`, but the target is taken from the `RenderTexturePool`.
+    pub fn downscaled_pooled(&self, factor: u32, target_filtering: TextureFiltering, stats: &mut RenderStats) -> PooledRenderTexture {
+        assert_expr!(factor != 0, "Scaling factor cannot be 0");
+
+        let mut target_rt = RenderTexturePool::get(uvec2((self.res.0 / factor).max(1), (self.res.1 / factor).max(1)), self.format, target_filtering);
         target_rt.clear(Color4::CLEAR);
         target_rt.render_with_shader(&self, &DefaultMaterials::def_blit_material(), BlendingMode::AlphaMix, stats);
 
@@ -142,6 +300,11 @@ impl RenderTexture {
         self.render_with_shader_ext(source, &DefaultMaterials::def_blit_material(), blending, rect, source_uvs, stats);
     }
 
+    /// Renders `source` tone mapped, scaling its colors by `exposure` first.
+    pub fn tonemap(&mut self, source: &Self, mapping: ToneMapping, exposure: f32, stats: &mut RenderStats) {
+        self.render_with_shader(source, &DefaultMaterials::tonemap_material(mapping, exposure), BlendingMode::AlphaMix, stats);
+    }
+
     /// Soure cannot be the Screen Render Texture.
     pub fn render_with_shader(&mut self, source: &Self, material: &Material, blending: BlendingMode, stats: &mut RenderStats) {
         self.render_with_shader_ext(source, material, blending, ScreenRect::new(ivec2::ZERO, ivec2(self.res.0 as i32, self.res.1 as i32)), Rect::IDENT, stats);
@@ -158,16 +321,12 @@ impl RenderTexture {
         let vert_data = [-1.0, -1.0, source_uvs.left(), source_uvs.down(), source.alpha, -1.0, 1.0, source_uvs.left(), source_uvs.up(), source.alpha, 1.0, 1.0, source_uvs.right(), source_uvs.up(), source.alpha, 1.0, -1.0, source_uvs.right(), source_uvs.down(), source.alpha];
         const TRI_DATA: [u32; 6] = [0, 1, 2, 2, 3, 0];
 
-        material.enable();
-
-        let vao = GlVAO::new();
-        vao.bind();
-
-        let vbo = GlBuffer::new(&vert_data, GlBufferKind::VBO, GlBufferUsage::StaticDraw);
-        let ebo = GlBuffer::new(&TRI_DATA, GlBufferKind::EBO, GlBufferUsage::StaticDraw);
+        material.enable(1);
 
-        vbo.bind();
-        ebo.bind();
+        let mut blit_buffers = BLIT_BUFFERS.write().unwrap();
+        let buffers = blit_buffers.get_or_insert_with(StreamBuffers::new);
+        buffers.upload(&vert_data, &TRI_DATA);
+        buffers.bind();
 
         verts::set_vertex_attribs(&[2, 2, 1]);
         source.use_texture(0);
@@ -201,6 +360,67 @@ impl RenderTexture {
         self.res
     }
 
+    /// Returns the format of the first color attachment.
+    pub fn format(&self) -> ColorFormat {
+        self.format
+    }
+
+    /// Returns the number of color attachments.
+    pub fn attachment_count(&self) -> usize {
+        return 1 + self.extra_col_tex.len();
+    }
+
+    /// Returns the color attachment `index` as a texture, so it can be sampled through a texture uniform.
+    /// - The first attachment is the one used as source when blitting, so `index` starts at 1.
+    pub fn attachment(&self, index: usize) -> &Texture {
+        assert_expr!(index != 0, "The first attachment can't be accessed as a texture, use it as a blit source instead");
+        assert_expr!(index < self.attachment_count(), "Attachment index out of bounds");
+
+        return &self.extra_col_tex[index - 1];
+    }
+
+    /// Reads the rendered pixels back from the GPU as RGBA.
+    /// - Rows are in the same order as `Texture::download`, the screen render texture is flipped to match it.
+    /// - HDR formats are clamped to `[0, 1]`, use `read_pixels_f32` to keep their full range.
+    pub fn read_pixels(&self) -> Pixels<'static> {
+        let row_len = self.res.0 as usize * 4;
+        let mut data = vec![0u8; row_len * self.res.1 as usize];
+
+        RenderTexture::bind(self);
+        gl_with_pack_alignment(1, || gl_call!(gl::ReadPixels(0, 0, self.res.0 as i32, self.res.1 as i32, gl::RGBA, gl::UNSIGNED_BYTE, data.as_mut_ptr() as *mut std::ffi::c_void)));
+        RenderTexture::unbind();
+
+        let data = if self.fbo == 0 {
+            data.chunks_exact(row_len).rev().flatten().copied().collect()
+        } else {
+            data.into_boxed_slice()
+        };
+
+        return Pixels::owned(data, self.res);
+    }
+
+    /// Reads the rendered pixels back from the GPU as RGBA floats, without clamping.
+    /// - Rows are in the same order as `read_pixels`.
+    pub fn read_pixels_f32(&self) -> Box<[f32]> {
+        let row_len = self.res.0 as usize * 4;
+        let mut data = vec![0f32; row_len * self.res.1 as usize];
+
+        RenderTexture::bind(self);
+        gl_call!(gl::ReadPixels(0, 0, self.res.0 as i32, self.res.1 as i32, gl::RGBA, gl::FLOAT, data.as_mut_ptr() as *mut std::ffi::c_void));
+        RenderTexture::unbind();
+
+        if self.fbo == 0 {
+            return data.chunks_exact(row_len).rev().flatten().copied().collect();
+        }
+
+        return data.into_boxed_slice();
+    }
+
+    /// Reads the rendered pixels back and saves them as a png.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Res<(), TextureError> {
+        return self.read_pixels().save_png(path);
+    }
+
     pub fn statify(mut self) -> Texture {
         assert_expr!(self.fbo != 0, "Can't statify a render texture to the screen.");
 
@@ -213,6 +433,13 @@ impl RenderTexture {
     pub(super) unsafe fn forget_tex(&mut self) {
         self.col_tex = 0;
     }
+
+    /// Attaches a `Depth24Stencil8` renderbuffer to the bound render texture if it has no depth / stencil attachment.
+    fn ensure_stencil(&mut self) {
+        if self.fbo != 0 && self.stencil_rb == 0 {
+            self.stencil_rb = internal::attach_depth_stencil(self.res, DepthStencilFormat::Depth24Stencil8);
+        }
+    }
 }
 
 impl Drop for RenderTexture {
@@ -224,17 +451,198 @@ impl Drop for RenderTexture {
         if self.col_tex != 0 {
             gl_call!(gl::DeleteTextures(1, &self.col_tex));
         }
+
+        if self.stencil_rb != 0 {
+            gl_call!(gl::DeleteRenderbuffers(1, &self.stencil_rb));
+        }
+    }
+}
+
+/// Recycles transient render textures across frames, instead of creating new ones every frame.<br>
+/// - Render textures are requested by resolution, format and filtering, and go back to the pool when their handle is dropped.
+/// - Free render textures that haven't been requested for `max_unused_frames` frames are released.
+pub struct RenderTexturePool {
+    free: Vec<PoolEntry>,
+    frame: u64,
+    max_unused_frames: u32,
+}
+
+struct PoolEntry {
+    key: PoolKey,
+    rt: RenderTexture,
+    last_used: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PoolKey {
+    res: (u32, u32),
+    format: ColorFormat,
+    filtering: TextureFiltering,
+}
+
+impl RenderTexturePool {
+    const fn new() -> Self {
+        Self { free: Vec::new(), frame: 0, max_unused_frames: 3 }
+    }
+
+    /// Returns a render texture with a single color attachment, reusing a free one if possible.
+    /// - Its contents are undefined, it should be cleared or fully overwritten.
+    pub fn get(res: uvec2, format: ColorFormat, filtering: TextureFiltering) -> PooledRenderTexture {
+        let key = PoolKey { res: (res.0, res.1), format, filtering };
+
+        let mut pool = RT_POOL.write().unwrap();
+        let mut rt = match pool.free.iter().position(|x| x.key == key) {
+            Some(i) => pool.free.swap_remove(i).rt,
+            None => RenderTexture::builder(res).filtering(filtering).color(format).build(),
+        };
+        rt.set_alpha(1.0);
+
+        return PooledRenderTexture { rt: Some(rt), key };
+    }
+
+    /// Returns a render texture with the resolution and format of the first attachment of `rt`.
+    pub fn get_like(rt: &RenderTexture, filtering: TextureFiltering) -> PooledRenderTexture {
+        return Self::get(rt.res, rt.format, filtering);
+    }
+
+    /// Sets after how many frames without being requested free render textures are released. Defaults to 3.
+    pub fn set_max_unused_frames(frames: u32) {
+        RT_POOL.write().unwrap().max_unused_frames = frames;
+    }
+
+    /// Returns the number of free render textures kept in the pool.
+    pub fn free_count() -> usize {
+        return RT_POOL.read().unwrap().free.len();
+    }
+
+    /// Releases every free render texture.
+    pub fn clear() {
+        RT_POOL.write().unwrap().free.clear();
+    }
+
+    pub(super) fn end_frame() {
+        let mut pool = RT_POOL.write().unwrap();
+        pool.frame += 1;
+
+        let (frame, max_unused_frames) = (pool.frame, pool.max_unused_frames as u64);
+        pool.free.retain(|x| frame - x.last_used <= max_unused_frames);
+    }
+}
+
+/// Render texture borrowed from the `RenderTexturePool`, returned to it when dropped.
+pub struct PooledRenderTexture {
+    rt: Option<RenderTexture>,
+    key: PoolKey,
+}
+
+impl Deref for PooledRenderTexture {
+    type Target = RenderTexture;
+
+    fn deref(&self) -> &Self::Target {
+        return self.rt.as_ref().unwrap();
+    }
+}
+
+impl DerefMut for PooledRenderTexture {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        return self.rt.as_mut().unwrap();
+    }
+}
+
+impl Drop for PooledRenderTexture {
+    fn drop(&mut self) {
+        let Some(rt) = self.rt.take() else { return };
+
+        let mut pool = RT_POOL.write().unwrap();
+        let last_used = pool.frame;
+        pool.free.push(PoolEntry { key: self.key, rt, last_used });
     }
 }
 
 pub struct SceneRenderData<'a> {
     pub(super) products: &'a [(u8, TargetBatchData)],
     pub(super) clear_col: Color4,
-    pub(super) cam: &'a mat3
+    pub(super) cam: &'a mat3,
+    pub(super) views: &'a [(View, mat3)],
 }
 
 impl<'a> SceneRenderData<'a> {
     pub fn clear_col(&self) -> Color4 {
         self.clear_col
     }
-}
\ No newline at end of file
+
+    pub fn view_count(&self) -> usize {
+        self.views.len()
+    }
+
+    pub fn view_at(&self, index: usize) -> &View {
+        return &self.views[index].0;
+    }
+
+    /// Returns the scene as seen through a single view, to render it into its own target.<br>
+    /// - The result has no views, so `render_scene` fills the whole target with it.
+    pub fn view(&self, index: usize) -> SceneRenderData<'a> {
+        let (_, cam) = &self.views[index];
+        return SceneRenderData { products: self.products, clear_col: self.clear_col, cam, views: &[] };
+    }
+}
+
+mod internal {
+    use crate::{graphics::{batch::TargetBatchData, gl_bindings::{gl_set_scissor, gl_set_stencil, GlStencilMode}, texture::TextureFiltering, uniforms::GlobalBlock, RenderStats}, math::{mat3, uvec2}};
+
+    use super::{gl_call, ColorFormat, DepthStencilFormat, RenderTexture, ScreenRect};
+
+    pub fn render_batches(rt: &mut RenderTexture, products: &[(u8, TargetBatchData)], cam: &mat3, rect: ScreenRect, target: u8, stats: &mut RenderStats) {
+        let size = uvec2((rect.r - rect.l).max(0) as u32, (rect.u - rect.d).max(0) as u32);
+        gl_call!(gl::Viewport(rect.l, rect.d, size.0 as i32, size.1 as i32));
+
+        RenderTexture::bind(rt);
+        GlobalBlock::upload(cam, size);
+
+        if let Some(products) = products.iter().find(|x| x.0 == target).map(|x| &x.1) {
+            if products.render_batches.iter().any(|x| x.uses_mask()) {
+                rt.ensure_stencil();
+            }
+
+            let mut last_mask_write = None;
+            for b in &products.render_batches {
+                b.render(cam, &rect, &mut last_mask_write);
+            }
+            gl_set_stencil(GlStencilMode::Disabled);
+            gl_set_scissor(None);
+            stats.draw_calls += products.render_batches.len();
+            stats.batch_draw_calls += products.render_batches.len();
+            stats.saved_draw_calls += products.render_saved_calls;
+        }
+
+        RenderTexture::unbind();
+    }
+
+    pub fn create_color_tex(res: uvec2, filtering: TextureFiltering, format: ColorFormat) -> gl::types::GLuint {
+        let (internal_fmt, fmt, kind) = format.gl_format();
+
+        let mut tex = 0;
+        gl_call!(gl::GenTextures(1, &mut tex));
+        gl_call!(gl::BindTexture(gl::TEXTURE_2D, tex));
+        gl_call!(gl::TexImage2D(gl::TEXTURE_2D, 0, internal_fmt as i32, res.0 as i32, res.1 as i32, 0, fmt, kind, std::ptr::null()));
+        gl_call!(gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, filtering as u32 as i32));
+        gl_call!(gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, filtering as u32 as i32));
+        gl_call!(gl::BindTexture(gl::TEXTURE_2D, 0));
+
+        return tex;
+    }
+
+    /// Creates a depth / stencil renderbuffer and attaches it to the bound framebuffer.
+    pub fn attach_depth_stencil(res: uvec2, format: DepthStencilFormat) -> gl::types::GLuint {
+        let (internal_fmt, attachment) = format.gl_format();
+
+        let mut rb = 0;
+        gl_call!(gl::GenRenderbuffers(1, &mut rb));
+        gl_call!(gl::BindRenderbuffer(gl::RENDERBUFFER, rb));
+        gl_call!(gl::RenderbufferStorage(gl::RENDERBUFFER, internal_fmt, res.0 as i32, res.1 as i32));
+        gl_call!(gl::BindRenderbuffer(gl::RENDERBUFFER, 0));
+
+        gl_call!(gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, attachment, gl::RENDERBUFFER, rb));
+        return rb;
+    }
+}
@@ -0,0 +1,374 @@
+use crate::{assert_expr, color::Color4, math::vec2};
+
+/// Shape of the corners of a polyline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    /// Sharp corner. Falls back to `Bevel` when the corner is longer than `miter_limit` times the width.
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// Shape of the ends of an open polyline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    /// Ends exactly at the end points.
+    Butt,
+    /// Extends past the end points by half of the width.
+    Square,
+    Round,
+}
+
+/// How a polyline is tessellated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineStyle {
+    /// Width in world units.
+    pub width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    /// Max length of a miter join, relative to the width.
+    pub miter_limit: f32,
+    /// Connects the last point with the first one. Closed lines have no caps, unless they are dashed.
+    pub closed: bool,
+    /// Lengths of alternating dashes and gaps, in world units. Empty for a solid line.<br>
+    /// - An odd number of lengths is repeated twice, so dashes and gaps alternate.
+    /// - Dashes of length zero are drawn as dots, unless the cap is `Butt`.
+    pub dash: Vec<f32>,
+    /// Distance into the dash pattern where the line starts.
+    pub dash_offset: f32,
+}
+
+impl Default for LineStyle {
+    fn default() -> Self {
+        Self { width: 0.1, join: LineJoin::Miter, cap: LineCap::Butt, miter_limit: 4.0, closed: false, dash: Vec::new(), dash_offset: 0.0 }
+    }
+}
+
+impl LineStyle {
+    pub fn new(width: f32, join: LineJoin, cap: LineCap) -> Self {
+        return Self { width, join, cap, ..Default::default() };
+    }
+}
+
+/// Tessellates a polyline into triangles. Vertices are laid out as a position followed by a color.<br>
+/// - `colors` must have either one color for the whole line or one color per point.
+/// - Translucent lines are blended twice where the joins overlap the segments.
+pub(super) fn tessellate(points: &[vec2], colors: &[Color4], style: &LineStyle) -> (Vec<f32>, Vec<u32>) {
+    assert_expr!(colors.len() == 1 || colors.len() == points.len(), "There must be either one color or one color per point.");
+    assert_expr!(style.width > 0.0, "Line width must be positive.");
+    assert_expr!(style.dash.iter().all(|x| *x >= 0.0), "Dash lengths must not be negative.");
+    assert_expr!(style.dash.is_empty() || style.dash.iter().sum::<f32>() > 0.0, "At least one dash length must be positive.");
+
+    let mut path: Vec<(vec2, Color4)> = Vec::with_capacity(points.len() + 1);
+    for (i, p) in points.iter().enumerate() {
+        if path.last().is_some_and(|x| internal::same_point(x.0, *p)) {
+            continue;
+        }
+
+        path.push((*p, colors[if colors.len() == 1 { 0 } else { i }]));
+    }
+
+    if style.closed && path.len() > 2 && internal::same_point(path[0].0, path[path.len() - 1].0) {
+        path.pop();
+    }
+
+    let mut tess = internal::Tessellator::new(style);
+    if path.len() < 2 {
+        return tess.finish();
+    }
+
+    if style.dash.is_empty() {
+        tess.add_path(&path, style.closed && path.len() > 2);
+    } else {
+        if style.closed {
+            path.push(path[0]);
+        }
+
+        let (dashes, dots) = internal::split_dashes(&path, &style.dash, style.dash_offset);
+        for dash in dashes {
+            tess.add_path(&dash, false);
+        }
+        for (p, col, dir) in dots {
+            tess.dot(p, col, dir);
+        }
+    }
+
+    return tess.finish();
+}
+
+
+mod internal {
+    use std::f32::consts::PI;
+
+    use crate::{color::Color4, math::{lerp, vec2}};
+
+    use super::{LineCap, LineJoin, LineStyle};
+
+    /// Max angle covered by a single triangle of a round join or cap.
+    const ROUND_STEP: f32 = PI / 12.0;
+
+    pub struct Tessellator<'a> {
+        style: &'a LineStyle,
+        half_width: f32,
+        verts: Vec<f32>,
+        tris: Vec<u32>,
+    }
+
+    impl<'a> Tessellator<'a> {
+        pub fn new(style: &'a LineStyle) -> Self {
+            return Self { style, half_width: style.width * 0.5, verts: Vec::new(), tris: Vec::new() };
+        }
+
+        pub fn finish(self) -> (Vec<f32>, Vec<u32>) {
+            return (self.verts, self.tris);
+        }
+
+        pub fn add_path(&mut self, path: &[(vec2, Color4)], closed: bool) {
+            let n = path.len();
+            let seg_count = if closed { n } else { n - 1 };
+            let dir = |i: usize| normalize(path[(i + 1) % n].0 - path[i].0);
+
+            for i in 0..seg_count {
+                let ((mut a, col_a), (mut b, col_b)) = (path[i], path[(i + 1) % n]);
+                let d = dir(i);
+
+                if !closed && self.style.cap == LineCap::Square {
+                    if i == 0 {
+                        a = a - d * self.half_width;
+                    }
+                    if i == seg_count - 1 {
+                        b = b + d * self.half_width;
+                    }
+                }
+
+                self.segment(a, b, col_a, col_b, d);
+            }
+
+            let joins = if closed { 0..n } else { 1..n - 1 };
+            for i in joins {
+                let prev = (i + n - 1) % n;
+                self.join(path[i].0, path[i].1, dir(prev), dir(i));
+            }
+
+            if !closed && self.style.cap == LineCap::Round {
+                let first = dir(0);
+                let last = dir(n - 2);
+                self.fan(path[0].0, path[0].1, perp(first * -1.0) * self.half_width, -PI);
+                self.fan(path[n - 1].0, path[n - 1].1, perp(last) * self.half_width, -PI);
+            }
+        }
+
+        /// Caps of a dash of length zero at `p`, facing `dir`.
+        pub fn dot(&mut self, p: vec2, col: Color4, dir: vec2) {
+            match self.style.cap {
+                LineCap::Butt => {},
+                LineCap::Square => self.segment(p - dir * self.half_width, p + dir * self.half_width, col, col, dir),
+                LineCap::Round => self.fan(p, col, perp(dir) * self.half_width, 2.0 * PI),
+            }
+        }
+
+        fn segment(&mut self, a: vec2, b: vec2, col_a: Color4, col_b: Color4, dir: vec2) {
+            let offset = perp(dir) * self.half_width;
+            let i = self.vert(a + offset, col_a);
+            self.vert(a - offset, col_a);
+            self.vert(b - offset, col_b);
+            self.vert(b + offset, col_b);
+
+            self.tris.extend_from_slice(&[i, i + 1, i + 2, i + 2, i + 3, i]);
+        }
+
+        fn join(&mut self, p: vec2, col: Color4, d0: vec2, d1: vec2) {
+            let turn = cross(d0, d1);
+            if turn.abs() < 1e-6 && dot(d0, d1) > 0.0 {
+                return;
+            }
+
+            // Offsets towards the outer side of the corner
+            let side = if turn > 0.0 { -1.0 } else { 1.0 };
+            let o0 = perp(d0) * (self.half_width * side);
+            let o1 = perp(d1) * (self.half_width * side);
+
+            match self.style.join {
+                LineJoin::Miter => {
+                    let bisector = o0 + o1;
+                    let cos_half = length(bisector) / (2.0 * self.half_width);
+                    if cos_half < 1e-6 || 1.0 / cos_half > self.style.miter_limit {
+                        self.bevel(p, col, o0, o1);
+                        return;
+                    }
+
+                    let tip = p + normalize(bisector) * (self.half_width / cos_half);
+                    let i = self.vert(p, col);
+                    self.vert(p + o0, col);
+                    self.vert(tip, col);
+                    self.vert(p + o1, col);
+                    self.tris.extend_from_slice(&[i, i + 1, i + 2, i, i + 2, i + 3]);
+                },
+                LineJoin::Bevel => self.bevel(p, col, o0, o1),
+                LineJoin::Round => {
+                    // The arc goes from `o0` towards `d0` and ends at `o1`
+                    let angle = (dot(o0, o1) / (self.half_width * self.half_width)).clamp(-1.0, 1.0).acos();
+                    let angle = if cross(o0, d0) > 0.0 { angle } else { -angle };
+                    self.fan(p, col, o0, angle);
+                },
+            }
+        }
+
+        fn bevel(&mut self, p: vec2, col: Color4, o0: vec2, o1: vec2) {
+            let i = self.vert(p, col);
+            self.vert(p + o0, col);
+            self.vert(p + o1, col);
+            self.tris.extend_from_slice(&[i, i + 1, i + 2]);
+        }
+
+        /// Triangle fan around `center`, starting at `from` and rotating counterclockwise by `angle`.
+        fn fan(&mut self, center: vec2, col: Color4, from: vec2, angle: f32) {
+            let steps = ((angle.abs() / ROUND_STEP).ceil() as u32).max(1);
+
+            let i = self.vert(center, col);
+            for k in 0..=steps {
+                self.vert(center + rotate(from, angle * k as f32 / steps as f32), col);
+            }
+            for k in 1..=steps {
+                self.tris.extend_from_slice(&[i, i + k, i + k + 1]);
+            }
+        }
+
+        fn vert(&mut self, pos: vec2, col: Color4) -> u32 {
+            let index = (self.verts.len() / 6) as u32;
+            self.verts.extend_from_slice(&[pos.0, pos.1, col.0, col.1, col.2, col.3]);
+            return index;
+        }
+    }
+
+    /// Splits a path into the parts covered by dashes.<br>
+    /// - Dashes that collapse to a single point are returned apart, as dots with the direction of the path there.
+    pub fn split_dashes(path: &[(vec2, Color4)], dash: &[f32], offset: f32) -> (Vec<Vec<(vec2, Color4)>>, Vec<(vec2, Color4, vec2)>) {
+        let pattern = if dash.len() % 2 == 0 { dash.to_vec() } else { [dash, dash].concat() };
+        let total: f32 = pattern.iter().sum();
+
+        // Find where the pattern starts
+        let mut index = 0;
+        let mut remaining = pattern[0];
+        let mut phase = offset.rem_euclid(total);
+        // A dash of length zero at the start of the pattern is kept
+        while phase > remaining || (phase == remaining && remaining > 0.0) {
+            phase -= remaining;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+        remaining -= phase;
+
+        let mut dashes = Vec::new();
+        let mut dots = Vec::new();
+        let mut close = |mut dash: Vec<(vec2, Color4)>, dir: vec2| {
+            dash.dedup_by(|a, b| same_point(a.0, b.0));
+            match dash.len() {
+                0 => {},
+                1 => dots.push((dash[0].0, dash[0].1, dir)),
+                _ => dashes.push(dash),
+            }
+        };
+
+        let mut dir = vec2::ZERO;
+        let mut current = if index % 2 == 0 { vec![path[0]] } else { Vec::new() };
+        for seg in path.windows(2) {
+            let ((a, col_a), (b, col_b)) = (seg[0], seg[1]);
+            let len = length(b - a);
+            dir = normalize(b - a);
+
+            let mut t = 0.0;
+            while len - t > remaining {
+                t += remaining;
+                current.push((lerp(a, b, t / len), col_a.mix(col_b, t / len)));
+
+                // The end of a dash closes it, the end of a gap starts a new one
+                if index % 2 == 0 {
+                    close(std::mem::take(&mut current), dir);
+                }
+
+                index = (index + 1) % pattern.len();
+                remaining = pattern[index];
+            }
+
+            remaining -= len - t;
+            if index % 2 == 0 {
+                current.push((b, col_b));
+            }
+        }
+
+        if index % 2 == 0 {
+            close(current, dir);
+        }
+
+        return (dashes, dots);
+    }
+
+    pub fn same_point(a: vec2, b: vec2) -> bool {
+        return (a.0 - b.0).abs() < 1e-6 && (a.1 - b.1).abs() < 1e-6;
+    }
+
+    fn perp(v: vec2) -> vec2 {
+        return vec2(-v.1, v.0);
+    }
+
+    fn rotate(v: vec2, angle: f32) -> vec2 {
+        let (s, c) = angle.sin_cos();
+        return vec2(v.0 * c - v.1 * s, v.0 * s + v.1 * c);
+    }
+
+    fn dot(a: vec2, b: vec2) -> f32 {
+        return a.0 * b.0 + a.1 * b.1;
+    }
+
+    fn cross(a: vec2, b: vec2) -> f32 {
+        return a.0 * b.1 - a.1 * b.0;
+    }
+
+    fn length(v: vec2) -> f32 {
+        return dot(v, v).sqrt();
+    }
+
+    fn normalize(v: vec2) -> vec2 {
+        return v * (1.0 / length(v));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{color::{Color, Color4}, math::vec2};
+
+    use super::{tessellate, LineCap, LineJoin, LineStyle};
+
+    #[test]
+    fn straight_line() {
+        let (verts, tris) = tessellate(&[vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(2.0, 0.0)], &[Color4::WHITE], &LineStyle::new(0.2, LineJoin::Miter, LineCap::Butt));
+
+        // Collinear points need no join
+        assert_eq!(verts.len() / 6, 8);
+        assert_eq!(tris.len(), 12);
+        assert!(verts.chunks(6).all(|x| (x[1].abs() - 0.1).abs() < 1e-6));
+    }
+
+    #[test]
+    fn dashes() {
+        let style = LineStyle { dash: vec![1.0, 1.0], ..LineStyle::new(0.2, LineJoin::Bevel, LineCap::Butt) };
+        let (_, tris) = tessellate(&[vec2(0.0, 0.0), vec2(5.0, 0.0)], &[Color4::WHITE], &style);
+
+        // Dashes at [0, 1], [2, 3] and [4, 5]
+        assert_eq!(tris.len(), 3 * 6);
+    }
+
+    #[test]
+    fn dots() {
+        let points = [vec2(0.0, 0.0), vec2(4.5, 0.0)];
+        let dotted = |cap| tessellate(&points, &[Color4::WHITE], &LineStyle { dash: vec![0.0, 1.0], ..LineStyle::new(0.2, LineJoin::Bevel, cap) });
+
+        // Dots at 0, 1, 2, 3 and 4, which only have caps
+        assert!(dotted(LineCap::Butt).1.is_empty());
+        assert_eq!(dotted(LineCap::Square).1.len(), 5 * 6);
+
+        let (verts, _) = dotted(LineCap::Round);
+        assert!(verts.chunks(6).all(|x| (x[0] - x[0].round()).abs() <= 0.1 + 1e-6));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,306 @@
+use std::sync::RwLock;
+
+use crate::{assert_expr, color::{Color, Color4}, math::uvec2, Res};
+
+use super::{defaults::{DefaultMaterials, DefaultShaders}, material::Material, pipeline::{RenderTexture, RenderTexturePool}, preprocessor::BuiltinProvider, shader::{Shader, ShaderError, SubShader, SubShaderType}, texture::{Texture, TextureCfg, TextureFiltering, TextureFormat}, uniforms::Uniform, BlendFactor, BlendOp, BlendingMode, RenderStats};
+
+const BLUR_FRAG: &str = include_str!("../inline/post/blur.frag");
+const THRESHOLD_FRAG: &str = include_str!("../inline/post/threshold.frag");
+const BLOOM_FRAG: &str = include_str!("../inline/post/bloom.frag");
+const VIGNETTE_FRAG: &str = include_str!("../inline/post/vignette.frag");
+const CHROMATIC_ABERRATION_FRAG: &str = include_str!("../inline/post/chromatic_aberration.frag");
+const CRT_FRAG: &str = include_str!("../inline/post/crt.frag");
+const PIXELATE_FRAG: &str = include_str!("../inline/post/pixelate.frag");
+const DITHER_FRAG: &str = include_str!("../inline/post/dither.frag");
+const LUT_FRAG: &str = include_str!("../inline/post/lut.frag");
+
+/// Overwrites the target, effects are meant to replace its contents.
+const REPLACE: BlendingMode = BlendingMode::Custom { src: BlendFactor::One, dst: BlendFactor::Zero, op: BlendOp::Add, alpha_src: BlendFactor::One, alpha_dst: BlendFactor::Zero, alpha_op: BlendOp::Add };
+
+/// Compiled the first time an effect is applied.
+static MATERIALS: RwLock<Option<PostMaterials>> = RwLock::new(None);
+
+/// A post processing pass, usable from a custom `RenderPipeline`.<br>
+/// - Intermediate render textures are taken from the `RenderTexturePool`.
+pub trait PostEffect {
+    /// Renders `source` with the effect into `target`, replacing its contents.
+    fn apply(&self, source: &RenderTexture, target: &mut RenderTexture, stats: &mut RenderStats);
+}
+
+/// Separable gaussian blur, done in a horizontal and a vertical pass.<br>
+/// - Pairs of texels are read with a single linear sample, so `source` should use `TextureFiltering::Linear`.
+#[derive(Debug, Clone, Copy)]
+pub struct GaussianBlur {
+    /// Standard deviation in pixels. Samples are taken up to `3 * sigma` pixels away.
+    pub sigma: f32,
+}
+
+impl Default for GaussianBlur {
+    fn default() -> Self {
+        Self { sigma: 4.0 }
+    }
+}
+
+impl PostEffect for GaussianBlur {
+    fn apply(&self, source: &RenderTexture, target: &mut RenderTexture, stats: &mut RenderStats) {
+        assert_expr!(self.sigma > 0.0, "Sigma must be positive.");
+
+        let mut horizontal = RenderTexturePool::get_like(source, TextureFiltering::Linear);
+        let mut material = internal::material(|x| &x.blur);
+        material.set_uniform_by_name(b"sigma\0", Uniform::Float(self.sigma));
+
+        material.set_uniform_by_name(b"direction\0", Uniform::Float2(1.0, 0.0));
+        horizontal.render_with_shader(source, &material, REPLACE, stats);
+
+        material.set_uniform_by_name(b"direction\0", Uniform::Float2(0.0, 1.0));
+        target.render_with_shader(&horizontal, &material, REPLACE, stats);
+    }
+}
+
+/// Adds a blurred version of the bright parts of the image on top of it.<br>
+/// - Works best with HDR render textures, where values can go over 1.
+#[derive(Debug, Clone, Copy)]
+pub struct Bloom {
+    /// Luminance over which pixels start to glow.
+    pub threshold: f32,
+    /// Scale of the glow, can go over 1.
+    pub intensity: f32,
+    /// Standard deviation of the blur, in pixels of the downscaled texture.
+    pub sigma: f32,
+    /// The bright parts are blurred at `1 / downscale` of the resolution.
+    pub downscale: u32,
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self { threshold: 1.0, intensity: 1.0, sigma: 4.0, downscale: 4 }
+    }
+}
+
+impl PostEffect for Bloom {
+    fn apply(&self, source: &RenderTexture, target: &mut RenderTexture, stats: &mut RenderStats) {
+        assert_expr!(self.downscale != 0, "Downscale factor cannot be 0.");
+
+        let res = source.res();
+        let res = uvec2((res.0 / self.downscale).max(1), (res.1 / self.downscale).max(1));
+
+        let mut bright = RenderTexturePool::get(res, source.format(), TextureFiltering::Linear);
+        let mut material = internal::material(|x| &x.threshold);
+        material.set_uniform_by_name(b"threshold\0", Uniform::Float(self.threshold));
+        bright.render_with_shader(source, &material, REPLACE, stats);
+
+        let mut blurred = RenderTexturePool::get_like(&bright, TextureFiltering::Linear);
+        GaussianBlur { sigma: self.sigma }.apply(&bright, &mut blurred, stats);
+
+        let mut material = internal::material(|x| &x.bloom);
+        material.set_uniform_by_name(b"intensity\0", Uniform::Float(self.intensity));
+
+        target.render_with_shader(source, &DefaultMaterials::def_blit_material(), REPLACE, stats);
+        target.render_with_shader(&blurred, &material, BlendingMode::Additive, stats);
+    }
+}
+
+/// Darkens the borders of the image.
+#[derive(Debug, Clone, Copy)]
+pub struct Vignette {
+    /// Color of the borders, its alpha scales the intensity.
+    pub color: Color4,
+    pub intensity: f32,
+    /// Distance from the center where the vignette starts, where 1 is the corners.
+    pub radius: f32,
+    /// Distance over which the vignette fades in.
+    pub softness: f32,
+}
+
+impl Default for Vignette {
+    fn default() -> Self {
+        Self { color: Color4::BLACK, intensity: 1.0, radius: 0.5, softness: 0.5 }
+    }
+}
+
+impl PostEffect for Vignette {
+    fn apply(&self, source: &RenderTexture, target: &mut RenderTexture, stats: &mut RenderStats) {
+        let mut material = internal::material(|x| &x.vignette);
+        material.set_uniform_by_name(b"vignette_col\0", Uniform::Float4(self.color.0, self.color.1, self.color.2, self.color.3));
+        material.set_uniform_by_name(b"intensity\0", Uniform::Float(self.intensity));
+        material.set_uniform_by_name(b"radius\0", Uniform::Float(self.radius));
+        material.set_uniform_by_name(b"softness\0", Uniform::Float(self.softness));
+
+        target.render_with_shader(source, &material, REPLACE, stats);
+    }
+}
+
+/// Splits the red and blue channels towards the borders of the image.
+#[derive(Debug, Clone, Copy)]
+pub struct ChromaticAberration {
+    /// Offset of the channels at the corners, in pixels.
+    pub offset: f32,
+}
+
+impl Default for ChromaticAberration {
+    fn default() -> Self {
+        Self { offset: 4.0 }
+    }
+}
+
+impl PostEffect for ChromaticAberration {
+    fn apply(&self, source: &RenderTexture, target: &mut RenderTexture, stats: &mut RenderStats) {
+        let mut material = internal::material(|x| &x.chromatic_aberration);
+        material.set_uniform_by_name(b"offset\0", Uniform::Float(self.offset));
+
+        target.render_with_shader(source, &material, REPLACE, stats);
+    }
+}
+
+/// Curved screen with scanlines.
+#[derive(Debug, Clone, Copy)]
+pub struct Crt {
+    /// Barrel distortion, 0 for a flat screen.
+    pub curvature: f32,
+    /// How dark the scanlines are, from 0 to 1.
+    pub scanline_intensity: f32,
+    /// Number of scanlines from top to bottom.
+    pub scanline_count: f32,
+}
+
+impl Default for Crt {
+    fn default() -> Self {
+        Self { curvature: 0.1, scanline_intensity: 0.3, scanline_count: 240.0 }
+    }
+}
+
+impl PostEffect for Crt {
+    fn apply(&self, source: &RenderTexture, target: &mut RenderTexture, stats: &mut RenderStats) {
+        let mut material = internal::material(|x| &x.crt);
+        material.set_uniform_by_name(b"curvature\0", Uniform::Float(self.curvature));
+        material.set_uniform_by_name(b"scanline_intensity\0", Uniform::Float(self.scanline_intensity));
+        material.set_uniform_by_name(b"scanline_count\0", Uniform::Float(self.scanline_count));
+
+        target.render_with_shader(source, &material, REPLACE, stats);
+    }
+}
+
+/// Renders the image in blocks of a single color.
+#[derive(Debug, Clone, Copy)]
+pub struct Pixelate {
+    /// Size of each block in pixels.
+    pub pixel_size: u32,
+}
+
+impl Default for Pixelate {
+    fn default() -> Self {
+        Self { pixel_size: 4 }
+    }
+}
+
+impl PostEffect for Pixelate {
+    fn apply(&self, source: &RenderTexture, target: &mut RenderTexture, stats: &mut RenderStats) {
+        assert_expr!(self.pixel_size != 0, "Pixel size cannot be 0.");
+
+        let mut material = internal::material(|x| &x.pixelate);
+        material.set_uniform_by_name(b"pixel_size\0", Uniform::Float(self.pixel_size as f32));
+
+        target.render_with_shader(source, &material, REPLACE, stats);
+    }
+}
+
+/// Reduces the number of colors using a 4x4 Bayer pattern.
+#[derive(Debug, Clone, Copy)]
+pub struct Dither {
+    /// Number of values per channel.
+    pub levels: u32,
+    /// Size of each cell of the pattern, in pixels.
+    pub scale: u32,
+}
+
+impl Default for Dither {
+    fn default() -> Self {
+        Self { levels: 4, scale: 1 }
+    }
+}
+
+impl PostEffect for Dither {
+    fn apply(&self, source: &RenderTexture, target: &mut RenderTexture, stats: &mut RenderStats) {
+        assert_expr!(self.levels >= 2, "At least 2 levels are required.");
+        assert_expr!(self.scale != 0, "Scale cannot be 0.");
+
+        let mut material = internal::material(|x| &x.dither);
+        material.set_uniform_by_name(b"levels\0", Uniform::Float(self.levels as f32));
+        material.set_uniform_by_name(b"dither_scale\0", Uniform::Float(self.scale as f32));
+
+        target.render_with_shader(source, &material, REPLACE, stats);
+    }
+}
+
+/// Color grading through a 3D lookup table.<br>
+/// - The LUT is a `size² x size` texture made of `size` slices of the blue axis laid out horizontally, with red along x and green along y.
+/// - The LUT should use linear filtering and clamp wrapping.
+#[derive(Clone)]
+pub struct LutGrading {
+    pub lut: Texture,
+    /// Blend between the original (0) and the graded (1) colors.
+    pub intensity: f32,
+}
+
+impl PostEffect for LutGrading {
+    fn apply(&self, source: &RenderTexture, target: &mut RenderTexture, stats: &mut RenderStats) {
+        let dims = self.lut.dims();
+        assert_expr!(dims.0 == dims.1 * dims.1, "The LUT must be size² pixels wide and size pixels tall.");
+
+        let mut material = internal::material(|x| &x.lut);
+        material.set_uniform_by_name(b"lut_tex\0", Uniform::Texture(self.lut.clone()));
+        material.set_uniform_by_name(b"lut_size\0", Uniform::Float(dims.1 as f32));
+        material.set_uniform_by_name(b"intensity\0", Uniform::Float(self.intensity));
+
+        target.render_with_shader(source, &material, REPLACE, stats);
+    }
+}
+
+
+struct PostMaterials {
+    blur: Material,
+    threshold: Material,
+    bloom: Material,
+    vignette: Material,
+    chromatic_aberration: Material,
+    crt: Material,
+    pixelate: Material,
+    dither: Material,
+    lut: Material,
+}
+
+impl PostMaterials {
+    fn new() -> Res<Self, ShaderError> {
+        let blit = |src: &str, uniforms: &[(&[u8], Uniform)]| -> Res<Material, ShaderError> {
+            let frag = SubShader::preprocessed(src, SubShaderType::Frag, &[], &BuiltinProvider)?;
+            let shader = Shader::new(&DefaultShaders::def_blit_vert(), &frag)?;
+            return Ok(Material::new(&shader, uniforms));
+        };
+
+        return Ok(Self {
+            blur: blit(BLUR_FRAG, &[(b"direction\0", Uniform::Float2(1.0, 0.0)), (b"sigma\0", Uniform::Float(1.0))])?,
+            threshold: blit(THRESHOLD_FRAG, &[(b"threshold\0", Uniform::Float(1.0))])?,
+            bloom: blit(BLOOM_FRAG, &[(b"intensity\0", Uniform::Float(1.0))])?,
+            vignette: blit(VIGNETTE_FRAG, &[(b"vignette_col\0", Uniform::Float4(0.0, 0.0, 0.0, 1.0)), (b"intensity\0", Uniform::Float(1.0)), (b"radius\0", Uniform::Float(0.5)), (b"softness\0", Uniform::Float(0.5))])?,
+            chromatic_aberration: blit(CHROMATIC_ABERRATION_FRAG, &[(b"offset\0", Uniform::Float(0.0))])?,
+            crt: blit(CRT_FRAG, &[(b"curvature\0", Uniform::Float(0.0)), (b"scanline_intensity\0", Uniform::Float(0.0)), (b"scanline_count\0", Uniform::Float(1.0))])?,
+            pixelate: blit(PIXELATE_FRAG, &[(b"pixel_size\0", Uniform::Float(1.0))])?,
+            dither: blit(DITHER_FRAG, &[(b"levels\0", Uniform::Float(2.0)), (b"dither_scale\0", Uniform::Float(1.0))])?,
+            lut: blit(LUT_FRAG, &[(b"lut_tex\0", Uniform::Texture(Texture::empty(TextureFormat::RGBA, uvec2(1, 1), TextureCfg::default()))), (b"lut_size\0", Uniform::Float(1.0)), (b"intensity\0", Uniform::Float(1.0))])?,
+        });
+    }
+}
+
+
+mod internal {
+    use crate::{graphics::material::Material, unwrap_res};
+
+    use super::{PostMaterials, MATERIALS};
+
+    /// Returns a copy of one of the effect materials, compiling them if needed.
+    pub fn material(select: impl FnOnce(&PostMaterials) -> &Material) -> Material {
+        let mut materials = MATERIALS.write().unwrap();
+        let materials = materials.get_or_insert_with(|| unwrap_res!(PostMaterials::new()));
+        return select(materials).clone();
+    }
+}
\ No newline at end of file
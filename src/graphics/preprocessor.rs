@@ -0,0 +1,302 @@
+use std::{collections::{HashMap, HashSet}, path::PathBuf};
+
+use crate::Res;
+
+use super::shader::{Shader, ShaderError, SubShader, SubShaderType};
+
+/// Snippets available to every shader under the `nogine/` prefix.
+/// - `nogine/blit.glsl`: Inputs and outputs of blit frag shaders.
+/// - `nogine/color.glsl`: Color space and luminance helpers.
+/// - `nogine/globals.glsl`: Declaration of the per-frame `Globals` uniform block.
+/// - `nogine/frag_data.glsl`: `o_FragData[NOGINE_FRAG_DATA_COUNT]` output array, one element per color attachment.
+pub const BUILTIN_INCLUDES: &[(&str, &str)] = &[
+    ("nogine/blit.glsl", include_str!("../inline/include/blit.glsl")),
+    ("nogine/color.glsl", include_str!("../inline/include/color.glsl")),
+    ("nogine/globals.glsl", include_str!("../inline/include/globals.glsl")),
+    ("nogine/frag_data.glsl", include_str!("../inline/include/frag_data.glsl")),
+];
+
+/// Resolves the files referenced by `#include` directives.
+pub trait SourceProvider {
+    /// Returns the source of `path`, or `None` if it doesn't exist.
+    fn load(&self, path: &str) -> Option<String>;
+}
+
+/// Provider with no files, only the builtin includes are available.
+pub struct BuiltinProvider;
+
+impl SourceProvider for BuiltinProvider {
+    fn load(&self, _path: &str) -> Option<String> {
+        return None;
+    }
+}
+
+/// Loads includes from disk, relative to a root directory.
+pub struct FileProvider {
+    root: PathBuf,
+}
+
+impl FileProvider {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        return Self { root: root.into() };
+    }
+}
+
+impl SourceProvider for FileProvider {
+    fn load(&self, path: &str) -> Option<String> {
+        return std::fs::read_to_string(self.root.join(path)).ok();
+    }
+}
+
+/// Loads includes from memory.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryProvider {
+    files: HashMap<String, String>,
+}
+
+impl MemoryProvider {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Adds a file.
+    pub fn with(mut self, path: impl Into<String>, src: impl Into<String>) -> Self {
+        self.insert(path, src);
+        return self;
+    }
+
+    /// Adds or replaces a file.
+    pub fn insert(&mut self, path: impl Into<String>, src: impl Into<String>) {
+        self.files.insert(path.into(), src.into());
+    }
+}
+
+impl SourceProvider for MemoryProvider {
+    fn load(&self, path: &str) -> Option<String> {
+        return self.files.get(path).cloned();
+    }
+}
+
+
+/// Where a line of preprocessed source comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineOrigin {
+    /// Line added by the preprocessor, like injected defines.
+    Injected,
+    /// Line (starting at 1) of the main source.
+    Main(u32),
+    /// Line (starting at 1) of an included file.
+    Include { path: String, line: u32 },
+}
+
+/// Result of preprocessing a source.
+#[derive(Debug, Clone)]
+pub struct Preprocessed {
+    pub source: String,
+    /// Origin of every line of `source`.
+    pub lines: Vec<LineOrigin>,
+}
+
+/// Resolves `#include "file"` directives through `provider` and injects `defines` right after the `#version` directive, or at the start if there is none.<br>
+/// - Builtin includes (`nogine/...`) take precedence over the provider.
+/// - Includes inside of an included file are relative to that file, the paths given to `provider` are relative to the main source.
+/// - Recursive includes are reported as errors.
+/// - Files containing `#pragma once` are only included the first time, every other file is included as many times as it appears.
+pub fn preprocess(src: &str, defines: &[(&str, &str)], provider: &dyn SourceProvider) -> Res<Preprocessed, ShaderError> {
+    let mut out = Preprocessed { source: String::with_capacity(src.len()), lines: Vec::new() };
+    let mut once = HashSet::new();
+
+    let version_line = src.lines().position(|x| x.trim_start().starts_with("#version"));
+    if version_line.is_none() {
+        internal::push_defines(&mut out, defines);
+    }
+
+    for (i, line) in src.lines().enumerate() {
+        match internal::parse_include(line)? {
+            Some(path) => internal::push_include(&mut out, path, provider, &mut Vec::new(), &mut once)?,
+            None => internal::push_line(&mut out, line, LineOrigin::Main(i as u32 + 1)),
+        }
+
+        if version_line == Some(i) {
+            internal::push_defines(&mut out, defines);
+        }
+    }
+
+    return Ok(out);
+}
+
+
+/// Compiles variants of a shader on demand and caches them by their define set.
+pub struct ShaderVariants {
+    vert: String,
+    frag: String,
+    provider: Box<dyn SourceProvider + Send + Sync>,
+    cache: HashMap<Vec<(String, String)>, Shader>,
+}
+
+impl ShaderVariants {
+    pub fn new(vert: impl Into<String>, frag: impl Into<String>, provider: impl SourceProvider + Send + Sync + 'static) -> Self {
+        return Self { vert: vert.into(), frag: frag.into(), provider: Box::new(provider), cache: HashMap::new() };
+    }
+
+    /// Returns the variant for `defines`, compiling it if it's not cached.
+    /// - The order of the defines doesn't matter.
+    pub fn get(&mut self, defines: &[(&str, &str)]) -> Res<Shader, ShaderError> {
+        let mut key = defines.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<_>>();
+        key.sort();
+
+        if let Some(shader) = self.cache.get(&key) {
+            return Ok(shader.clone());
+        }
+
+        let vert = SubShader::preprocessed(&self.vert, SubShaderType::Vert, defines, self.provider.as_ref())?;
+        let frag = SubShader::preprocessed(&self.frag, SubShaderType::Frag, defines, self.provider.as_ref())?;
+        let shader = Shader::new(&vert, &frag)?;
+
+        self.cache.insert(key, shader.clone());
+        return Ok(shader);
+    }
+
+    /// Drops every compiled variant.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+
+mod internal {
+    use std::collections::HashSet;
+
+    use crate::{graphics::shader::ShaderError, Res};
+
+    use super::{LineOrigin, Preprocessed, SourceProvider, BUILTIN_INCLUDES};
+
+    pub fn push_line(out: &mut Preprocessed, line: &str, origin: LineOrigin) {
+        out.source.push_str(line);
+        out.source.push('\n');
+        out.lines.push(origin);
+    }
+
+    pub fn push_defines(out: &mut Preprocessed, defines: &[(&str, &str)]) {
+        for (k, v) in defines {
+            push_line(out, &format!("#define {k} {v}"), LineOrigin::Injected);
+        }
+    }
+
+    pub fn parse_include(line: &str) -> Res<Option<&str>, ShaderError> {
+        let Some(rest) = line.trim().strip_prefix("#include") else { return Ok(None) };
+
+        let rest = rest.trim();
+        return match rest.strip_prefix('"').and_then(|x| x.strip_suffix('"')) {
+            Some(path) => Ok(Some(path)),
+            None => Err(ShaderError::IncludeError { path: rest.into(), msg: "Include paths must be quoted.".into() }),
+        };
+    }
+
+    /// `once` keeps the paths of the files with `#pragma once` that were already included.
+    pub fn push_include(out: &mut Preprocessed, path: &str, provider: &dyn SourceProvider, stack: &mut Vec<String>, once: &mut HashSet<String>) -> Res<(), ShaderError> {
+        if stack.iter().any(|x| x == path) {
+            return Err(ShaderError::IncludeError { path: path.into(), msg: format!("Recursive include ({} -> {path}).", stack.join(" -> ")) });
+        }
+
+        let src = match BUILTIN_INCLUDES.iter().find(|x| x.0 == path) {
+            Some((_, src)) => src.to_string(),
+            None => provider.load(path).ok_or_else(|| ShaderError::IncludeError { path: path.into(), msg: "File not found.".into() })?,
+        };
+
+        if src.lines().any(is_pragma_once) && !once.insert(path.into()) {
+            return Ok(());
+        }
+
+        stack.push(path.into());
+        for (i, line) in src.lines().enumerate().filter(|x| !is_pragma_once(x.1)) {
+            match parse_include(line)? {
+                Some(inner) => push_include(out, &relative_to(path, inner), provider, stack, once)?,
+                None => push_line(out, line, LineOrigin::Include { path: path.into(), line: i as u32 + 1 }),
+            }
+        }
+        stack.pop();
+
+        return Ok(());
+    }
+
+    /// Resolves `path`, included from the file at `from`, relative to the directory of `from`.
+    fn relative_to(from: &str, path: &str) -> String {
+        if BUILTIN_INCLUDES.iter().any(|x| x.0 == path) {
+            return path.into();
+        }
+
+        let mut parts = from.split('/').collect::<Vec<_>>();
+        parts.pop();
+        for part in path.split('/') {
+            match part {
+                "" | "." => {},
+                ".." if parts.last().is_some_and(|x| *x != "..") => { parts.pop(); },
+                x => parts.push(x),
+            }
+        }
+
+        return parts.join("/");
+    }
+
+    fn is_pragma_once(line: &str) -> bool {
+        return line.split_whitespace().eq(["#pragma", "once"]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{preprocess, LineOrigin, MemoryProvider};
+
+    #[test]
+    fn includes_and_defines() {
+        let provider = MemoryProvider::new().with("a.glsl", "float a;\n#include \"b.glsl\"").with("b.glsl", "float b;");
+        let res = preprocess("#version 330 core\n#include \"a.glsl\"\nvoid main() {}", &[("FOO", "1")], &provider).unwrap();
+
+        assert_eq!(res.source, "#version 330 core\n#define FOO 1\nfloat a;\nfloat b;\nvoid main() {}\n");
+        assert_eq!(res.lines, vec![
+            LineOrigin::Main(1),
+            LineOrigin::Injected,
+            LineOrigin::Include { path: "a.glsl".into(), line: 1 },
+            LineOrigin::Include { path: "b.glsl".into(), line: 1 },
+            LineOrigin::Main(3),
+        ]);
+    }
+
+    #[test]
+    fn recursive_include() {
+        let provider = MemoryProvider::new().with("a.glsl", "#include \"b.glsl\"").with("b.glsl", "#include \"a.glsl\"");
+        assert!(preprocess("#include \"a.glsl\"", &[], &provider).is_err());
+    }
+
+    #[test]
+    fn pragma_once() {
+        let provider = MemoryProvider::new().with("a.glsl", "#include \"c.glsl\"").with("b.glsl", "#include \"c.glsl\"").with("c.glsl", "#pragma once\nfloat c;").with("d.glsl", "d();");
+        let res = preprocess("#include \"a.glsl\"\n#include \"b.glsl\"\n#include \"d.glsl\"\n#include \"d.glsl\"", &[], &provider).unwrap();
+
+        // Files without `#pragma once` are included every time
+        assert_eq!(res.source, "float c;\nd();\nd();\n");
+        assert_eq!(res.lines[0], LineOrigin::Include { path: "c.glsl".into(), line: 2 });
+    }
+
+    #[test]
+    fn nested_relative_includes() {
+        let provider = MemoryProvider::new()
+            .with("sub/a.glsl", "#include \"b.glsl\"\n#include \"../c.glsl\"\n#include \"nogine/color.glsl\"")
+            .with("sub/b.glsl", "float b;")
+            .with("c.glsl", "float c;");
+        let res = preprocess("#include \"sub/a.glsl\"", &[], &provider).unwrap();
+
+        assert!(res.source.starts_with("float b;\nfloat c;\n"));
+        assert_eq!(res.lines[1], LineOrigin::Include { path: "c.glsl".into(), line: 1 });
+    }
+
+    #[test]
+    fn defines_after_leading_comment() {
+        let res = preprocess("// Header\n\n#version 330 core\nvoid main() {}", &[("FOO", "1")], &MemoryProvider::new()).unwrap();
+        assert_eq!(res.source, "// Header\n\n#version 330 core\n#define FOO 1\nvoid main() {}\n");
+
+        let res = preprocess("void main() {}", &[("FOO", "1")], &MemoryProvider::new()).unwrap();
+        assert_eq!(res.source, "#define FOO 1\nvoid main() {}\n");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,186 @@
+use std::sync::Arc;
+
+use crate::log_warn;
+
+use super::{shader::Shader, uniforms::Uniform};
+
+/// Type of a shader variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlslType {
+    Float, Vec2, Vec3, Vec4,
+    Int, IVec2, IVec3, IVec4,
+    Uint, UVec2, UVec3, UVec4,
+    Bool, BVec2, BVec3, BVec4,
+    Mat2, Mat3, Mat4,
+    Sampler2D,
+    /// Any other type, with its GL enum.
+    Other(u32),
+}
+
+impl GlslType {
+    fn from_gl(kind: u32) -> Self {
+        match kind {
+            gl::FLOAT => Self::Float,
+            gl::FLOAT_VEC2 => Self::Vec2,
+            gl::FLOAT_VEC3 => Self::Vec3,
+            gl::FLOAT_VEC4 => Self::Vec4,
+            gl::INT => Self::Int,
+            gl::INT_VEC2 => Self::IVec2,
+            gl::INT_VEC3 => Self::IVec3,
+            gl::INT_VEC4 => Self::IVec4,
+            gl::UNSIGNED_INT => Self::Uint,
+            gl::UNSIGNED_INT_VEC2 => Self::UVec2,
+            gl::UNSIGNED_INT_VEC3 => Self::UVec3,
+            gl::UNSIGNED_INT_VEC4 => Self::UVec4,
+            gl::BOOL => Self::Bool,
+            gl::BOOL_VEC2 => Self::BVec2,
+            gl::BOOL_VEC3 => Self::BVec3,
+            gl::BOOL_VEC4 => Self::BVec4,
+            gl::FLOAT_MAT2 => Self::Mat2,
+            gl::FLOAT_MAT3 => Self::Mat3,
+            gl::FLOAT_MAT4 => Self::Mat4,
+            gl::SAMPLER_2D => Self::Sampler2D,
+            x => Self::Other(x),
+        }
+    }
+
+    /// Number of floats of a vertex attribute of this type.
+    pub fn float_components(&self) -> Option<usize> {
+        match self {
+            Self::Float => Some(1),
+            Self::Vec2 => Some(2),
+            Self::Vec3 => Some(3),
+            Self::Vec4 => Some(4),
+            _ => None,
+        }
+    }
+
+    /// Returns if `uniform` can be uploaded to a variable of this type.
+    /// - Arrays are checked against the type of their elements.
+    pub fn accepts(&self, uniform: &Uniform) -> bool {
+        match uniform {
+            Uniform::Float(_) => *self == Self::Float,
+            Uniform::Float2(..) => *self == Self::Vec2,
+            Uniform::Float3(..) => *self == Self::Vec3,
+            Uniform::Float4(..) => *self == Self::Vec4,
+            Uniform::Int(_) => matches!(self, Self::Int | Self::Bool | Self::Sampler2D),
+            Uniform::Int2(..) => matches!(self, Self::IVec2 | Self::BVec2),
+            Uniform::Int3(..) => matches!(self, Self::IVec3 | Self::BVec3),
+            Uniform::Int4(..) => matches!(self, Self::IVec4 | Self::BVec4),
+            Uniform::Uint(_) => matches!(self, Self::Uint | Self::Bool),
+            Uniform::Uint2(..) => matches!(self, Self::UVec2 | Self::BVec2),
+            Uniform::Uint3(..) => matches!(self, Self::UVec3 | Self::BVec3),
+            Uniform::Uint4(..) => matches!(self, Self::UVec4 | Self::BVec4),
+            Uniform::Bool(_) => *self == Self::Bool,
+            Uniform::Mat2(_) => *self == Self::Mat2,
+            Uniform::Mat3(_) => *self == Self::Mat3,
+            Uniform::Mat4(_) => *self == Self::Mat4,
+            Uniform::Texture(_) => *self == Self::Sampler2D,
+            Uniform::Array(x) => x.iter().all(|x| self.accepts(x)),
+        }
+    }
+}
+
+/// An active uniform or vertex attribute of a shader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderVariable {
+    /// Arrays are reported as `name[0]`.
+    pub name: String,
+    pub kind: GlslType,
+    /// Number of elements, 1 for non-arrays.
+    pub size: i32,
+    pub location: i32,
+}
+
+impl Shader {
+    /// Returns the active uniforms. Uniforms that are optimized out by the driver are not reported.
+    /// - Uniforms inside blocks have a location of -1.
+    pub fn uniforms(&self) -> Vec<ShaderVariable> {
+        return self.cached_uniforms().to_vec();
+    }
+
+    /// Returns the active uniforms, only querying them again after the shader is hot reloaded.
+    pub(super) fn cached_uniforms(&self) -> Arc<[ShaderVariable]> {
+        return self.gl_program().uniforms(|id| internal::active_variables(id, false));
+    }
+
+    /// Returns the active vertex attributes.
+    pub fn attributes(&self) -> Vec<ShaderVariable> {
+        return internal::active_variables(self.id(), true);
+    }
+}
+
+/// Warns about uniforms whose values can't be uploaded to the shader variable they target.
+pub(super) fn validate_uniform(shader: &Shader, name: &str, location: i32, value: &Uniform) {
+    let uniforms = shader.cached_uniforms();
+    let Some(var) = uniforms.iter().find(|x| x.location == location) else { return };
+
+    if !var.kind.accepts(value) {
+        log_warn!("Uniform '{name}' is of type {:?}, which doesn't accept {value:?}.", var.kind);
+    } else if let Uniform::Array(x) = value {
+        if x.len() > var.size as usize {
+            log_warn!("Uniform '{name}' has {} elements, but {} were provided.", var.size, x.len());
+        }
+    }
+}
+
+/// Warns about vertex attributes that don't match `vert_attribs`.
+/// - `vert_attribs[i]` is the number of floats of the attribute at location `i`.
+pub(super) fn validate_attribs(shader: &Shader, vert_attribs: &[usize]) {
+    for attr in shader.attributes() {
+        if attr.location < 0 {
+            continue;
+        }
+
+        let Some(&provided) = vert_attribs.get(attr.location as usize) else {
+            log_warn!("Attribute '{}' at location {} is not provided by the vertex layout {vert_attribs:?}.", attr.name, attr.location);
+            continue;
+        };
+
+        match attr.kind.float_components() {
+            Some(expected) if expected != provided => log_warn!("Attribute '{}' at location {} expects {expected} floats, but the vertex layout provides {provided}.", attr.name, attr.location),
+            None => log_warn!("Attribute '{}' at location {} is of type {:?}, only float attributes are supported.", attr.name, attr.location, attr.kind),
+            _ => (),
+        }
+    }
+}
+
+
+mod internal {
+    use crate::graphics::gl_call;
+
+    use super::{GlslType, ShaderVariable};
+
+    pub fn active_variables(program: u32, attributes: bool) -> Vec<ShaderVariable> {
+        let (count_enum, len_enum) = if attributes {
+            (gl::ACTIVE_ATTRIBUTES, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH)
+        } else {
+            (gl::ACTIVE_UNIFORMS, gl::ACTIVE_UNIFORM_MAX_LENGTH)
+        };
+
+        let (mut count, mut max_len) = (0, 0);
+        gl_call!(gl::GetProgramiv(program, count_enum, &mut count));
+        gl_call!(gl::GetProgramiv(program, len_enum, &mut max_len));
+
+        let mut buf = vec![0u8; max_len.max(1) as usize];
+        return (0..count as u32).map(|i| {
+            let (mut len, mut size, mut kind) = (0, 0, 0);
+            if attributes {
+                gl_call!(gl::GetActiveAttrib(program, i, buf.len() as i32, &mut len, &mut size, &mut kind, buf.as_mut_ptr() as *mut i8));
+            } else {
+                gl_call!(gl::GetActiveUniform(program, i, buf.len() as i32, &mut len, &mut size, &mut kind, buf.as_mut_ptr() as *mut i8));
+            }
+
+            // Includes the null terminator
+            let c_name = &buf[..len as usize + 1];
+            let location = if attributes {
+                gl_call!(gl::GetAttribLocation(program, c_name.as_ptr() as *const i8))
+            } else {
+                gl_call!(gl::GetUniformLocation(program, c_name.as_ptr() as *const i8))
+            };
+
+            let name = String::from_utf8_lossy(&c_name[..len as usize]).into_owned();
+            return ShaderVariable { name, kind: GlslType::from_gl(kind), size, location };
+        }).collect();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,424 @@
+use std::sync::RwLock;
+
+use thiserror::Error;
+
+use crate::{color::Color4, math::uvec2, Res};
+
+use super::{material::Material, pipeline::{ColorFormat, PooledRenderTexture, RenderPipeline, RenderTexture, RenderTexturePool, SceneRenderData, DEFAULT_RENDER_TARGET}, post::PostEffect, texture::TextureFiltering, BlendingMode, RenderStats};
+
+/// Name of the target the pipeline renders to, usually the screen.
+/// - It can only be written to.
+pub const SCREEN: &str = "screen";
+
+#[derive(Debug, Error)]
+pub enum RenderGraphError {
+    #[error("Pass '{pass}' uses the undeclared target '{target}'.")]
+    UnknownTarget { pass: String, target: String },
+    #[error("Pass '{pass}' reads '{target}' before any pass writes to it.")]
+    ReadBeforeWrite { pass: String, target: String },
+    #[error("Pass '{pass}' reads '{target}', which is also its output.")]
+    ReadsOwnTarget { pass: String, target: String },
+    #[error("Pass '{0}' reads the screen, which can only be written to.")]
+    ReadsScreen(String),
+    #[error("Pass '{pass}' needs {expected} inputs, but reads {found}.")]
+    InputCount { pass: String, expected: usize, found: usize },
+    #[error("Pass '{0}' is declared more than once.")]
+    DuplicatePass(String),
+    #[error("Target '{0}' is declared more than once.")]
+    DuplicateTarget(String),
+}
+
+/// Resolution of an intermediate target.
+#[derive(Debug, Clone, Copy)]
+pub enum TargetSize {
+    /// Resolution of the screen multiplied by a factor.
+    Scaled(f32),
+    Fixed(uvec2),
+}
+
+/// Declaration of an intermediate target.<br>
+/// - Defaults to a screen sized `RGBA8` target with linear filtering.
+#[derive(Debug, Clone, Copy)]
+pub struct TargetDesc {
+    pub size: TargetSize,
+    pub format: ColorFormat,
+    pub filtering: TextureFiltering,
+}
+
+impl Default for TargetDesc {
+    fn default() -> Self {
+        Self { size: TargetSize::Scaled(1.0), format: ColorFormat::RGBA8, filtering: TextureFiltering::Linear }
+    }
+}
+
+type CustomPassFn = dyn Fn(&[&RenderTexture], &mut RenderTexture, &SceneRenderData, &mut RenderStats);
+
+enum PassKind {
+    Scene(u8),
+    Ui,
+    Blit { material: Material, blending: BlendingMode },
+    Effect(Box<dyn PostEffect>),
+    Custom(Box<CustomPassFn>),
+}
+
+enum PassClear {
+    Color(Color4),
+    SceneColor,
+}
+
+/// A step of a `RenderGraph`, which reads from any number of targets and writes to one.<br>
+/// - Writes to `SCREEN` unless told otherwise.
+pub struct Pass {
+    name: String,
+    kind: PassKind,
+    reads: Vec<String>,
+    write: String,
+    clear: Option<PassClear>,
+}
+
+impl Pass {
+    fn new(name: impl Into<String>, kind: PassKind) -> Self {
+        return Self { name: name.into(), kind, reads: Vec::new(), write: SCREEN.into(), clear: None };
+    }
+
+    /// Renders the batches sent to `render_target`.
+    pub fn scene(name: impl Into<String>, render_target: u8) -> Self {
+        return Self::new(name, PassKind::Scene(render_target));
+    }
+
+    /// Renders the UI, if it's enabled.
+    pub fn ui(name: impl Into<String>) -> Self {
+        return Self::new(name, PassKind::Ui);
+    }
+
+    /// Renders the only input with a material.
+    pub fn blit(name: impl Into<String>, material: Material, blending: BlendingMode) -> Self {
+        return Self::new(name, PassKind::Blit { material, blending });
+    }
+
+    /// Applies a post processing effect to the only input.
+    pub fn effect(name: impl Into<String>, effect: impl PostEffect + 'static) -> Self {
+        return Self::new(name, PassKind::Effect(Box::new(effect)));
+    }
+
+    /// Runs custom rendering code, which receives the inputs in the order they were declared.
+    pub fn custom(name: impl Into<String>, func: impl Fn(&[&RenderTexture], &mut RenderTexture, &SceneRenderData, &mut RenderStats) + 'static) -> Self {
+        return Self::new(name, PassKind::Custom(Box::new(func)));
+    }
+
+    /// Adds an input.
+    pub fn read(mut self, target: impl Into<String>) -> Self {
+        self.reads.push(target.into());
+        return self;
+    }
+
+    /// Sets the output.
+    pub fn write(mut self, target: impl Into<String>) -> Self {
+        self.write = target.into();
+        return self;
+    }
+
+    /// Clears the output before running the pass.
+    pub fn clear(mut self, color: Color4) -> Self {
+        self.clear = Some(PassClear::Color(color));
+        return self;
+    }
+
+    /// Clears the output with the clear color of the scene before running the pass.
+    pub fn clear_to_scene(mut self) -> Self {
+        self.clear = Some(PassClear::SceneColor);
+        return self;
+    }
+}
+
+/// Stats of a pass in the last rendered frame.
+#[derive(Debug, Clone)]
+pub struct PassStats {
+    pub name: String,
+    /// Culled passes don't contribute to the screen, so they are never run.
+    pub culled: bool,
+    pub stats: RenderStats,
+}
+
+/// Declares the target and passes of a `RenderGraph`.
+#[derive(Default)]
+pub struct RenderGraphBuilder {
+    targets: Vec<(String, TargetDesc)>,
+    passes: Vec<Pass>,
+}
+
+impl RenderGraphBuilder {
+    /// Declares an intermediate target.
+    pub fn target(mut self, name: impl Into<String>, desc: TargetDesc) -> Self {
+        self.targets.push((name.into(), desc));
+        return self;
+    }
+
+    /// Adds a pass. Passes run in the order they are added.
+    pub fn pass(mut self, pass: Pass) -> Self {
+        self.passes.push(pass);
+        return self;
+    }
+
+    /// Validates the graph and culls the passes that don't contribute to the screen.
+    pub fn build(self) -> Res<RenderGraph, RenderGraphError> {
+        for (i, (name, _)) in self.targets.iter().enumerate() {
+            if name == SCREEN || self.targets[..i].iter().any(|x| &x.0 == name) {
+                return Err(RenderGraphError::DuplicateTarget(name.clone()));
+            }
+        }
+
+        let target_index = |pass: &Pass, target: &str| -> Res<Option<usize>, RenderGraphError> {
+            if target == SCREEN {
+                return Ok(None);
+            }
+
+            return match self.targets.iter().position(|x| x.0 == target) {
+                Some(i) => Ok(Some(i)),
+                None => Err(RenderGraphError::UnknownTarget { pass: pass.name.clone(), target: target.into() }),
+            };
+        };
+
+        let mut written = vec![false; self.targets.len()];
+        let mut nodes = Vec::with_capacity(self.passes.len());
+        for (i, pass) in self.passes.iter().enumerate() {
+            if self.passes[..i].iter().any(|x| x.name == pass.name) {
+                return Err(RenderGraphError::DuplicatePass(pass.name.clone()));
+            }
+
+            let expected = match pass.kind {
+                PassKind::Scene(_) | PassKind::Ui => Some(0),
+                PassKind::Blit { .. } | PassKind::Effect(_) => Some(1),
+                PassKind::Custom(_) => None,
+            };
+            if let Some(expected) = expected.filter(|x| *x != pass.reads.len()) {
+                return Err(RenderGraphError::InputCount { pass: pass.name.clone(), expected, found: pass.reads.len() });
+            }
+
+            let write = target_index(pass, &pass.write)?;
+            let mut reads = Vec::with_capacity(pass.reads.len());
+            for target in &pass.reads {
+                let Some(read) = target_index(pass, target)? else {
+                    return Err(RenderGraphError::ReadsScreen(pass.name.clone()));
+                };
+
+                if Some(read) == write {
+                    return Err(RenderGraphError::ReadsOwnTarget { pass: pass.name.clone(), target: target.clone() });
+                }
+
+                if !written[read] {
+                    return Err(RenderGraphError::ReadBeforeWrite { pass: pass.name.clone(), target: target.clone() });
+                }
+
+                reads.push(read);
+            }
+
+            if let Some(write) = write {
+                written[write] = true;
+            }
+
+            nodes.push(PassNode { reads, write, live: false });
+        }
+
+        internal::cull(&mut nodes, self.targets.len());
+
+        return Ok(RenderGraph { targets: self.targets.into_iter().map(|x| x.1).collect(), passes: self.passes, nodes, last_stats: RwLock::new(Vec::new()) });
+    }
+}
+
+/// Resolved targets of a pass.
+struct PassNode {
+    reads: Vec<usize>,
+    /// `None` for the screen.
+    write: Option<usize>,
+    live: bool,
+}
+
+/// Pipeline made of named passes that read and write named targets.<br>
+/// - Intermediate targets are taken from the `RenderTexturePool` when first written, cleared to transparent, and returned as soon as no other pass needs them.
+/// - Passes whose output never reaches the screen are culled.
+pub struct RenderGraph {
+    targets: Vec<TargetDesc>,
+    passes: Vec<Pass>,
+    nodes: Vec<PassNode>,
+    last_stats: RwLock<Vec<PassStats>>,
+}
+
+impl RenderGraph {
+    pub fn builder() -> RenderGraphBuilder {
+        return RenderGraphBuilder::default();
+    }
+
+    /// Returns the stats of every pass in the last rendered frame, in the order they were added.
+    pub fn last_stats(&self) -> Vec<PassStats> {
+        return self.last_stats.read().unwrap().clone();
+    }
+}
+
+impl RenderPipeline for RenderGraph {
+    fn render(&self, screen_rt: &mut RenderTexture, scene_data: &SceneRenderData, ui_data: Option<&SceneRenderData>, stats: &mut RenderStats) {
+        let screen_res = screen_rt.res();
+        let last_use = internal::last_use(&self.nodes, self.targets.len());
+
+        let mut allocated: Vec<Option<PooledRenderTexture>> = (0..self.targets.len()).map(|_| None).collect();
+        let mut pass_stats = Vec::with_capacity(self.passes.len());
+
+        for (i, (pass, node)) in self.passes.iter().zip(&self.nodes).enumerate() {
+            let mut local = RenderStats::default();
+            if !node.live {
+                pass_stats.push(PassStats { name: pass.name.clone(), culled: true, stats: local });
+                continue;
+            }
+
+            let mut output = node.write.map(|x| match allocated[x].take() {
+                Some(rt) => rt,
+                None => {
+                    let desc = &self.targets[x];
+                    let mut rt = RenderTexturePool::get(internal::resolve_size(desc.size, screen_res), desc.format, desc.filtering);
+                    rt.clear(Color4::CLEAR);
+                    rt
+                },
+            });
+            let target: &mut RenderTexture = match &mut output {
+                Some(rt) => rt,
+                None => &mut *screen_rt,
+            };
+
+            match pass.clear {
+                Some(PassClear::Color(color)) => target.clear(color),
+                Some(PassClear::SceneColor) => target.clear(scene_data.clear_col()),
+                None => (),
+            }
+
+            let inputs = node.reads.iter().map(|x| allocated[*x].as_deref().unwrap()).collect::<Vec<_>>();
+            match &pass.kind {
+                PassKind::Scene(render_target) => target.render_scene(scene_data, *render_target, &mut local),
+                PassKind::Ui => if let Some(ui_data) = ui_data {
+                    target.render_scene(ui_data, DEFAULT_RENDER_TARGET, &mut local);
+                },
+                PassKind::Blit { material, blending } => target.render_with_shader(inputs[0], material, *blending, &mut local),
+                PassKind::Effect(effect) => effect.apply(inputs[0], target, &mut local),
+                PassKind::Custom(func) => func(&inputs, target, scene_data, &mut local),
+            }
+
+            if let Some(write) = node.write {
+                allocated[write] = output;
+            }
+
+            // Return the targets nobody else needs, so the following passes can reuse them
+            for (rt, last) in allocated.iter_mut().zip(&last_use) {
+                if *last == Some(i) {
+                    *rt = None;
+                }
+            }
+
+            internal::add_stats(stats, &local);
+            pass_stats.push(PassStats { name: pass.name.clone(), culled: false, stats: local });
+        }
+
+        *self.last_stats.write().unwrap() = pass_stats;
+    }
+}
+
+
+mod internal {
+    use crate::{graphics::RenderStats, math::uvec2};
+
+    use super::{PassNode, TargetSize};
+
+    /// Marks as live the passes that write to the screen, and the ones that write to targets read by live passes.
+    pub fn cull(nodes: &mut [PassNode], target_count: usize) {
+        let mut needed = vec![false; target_count];
+        for node in nodes.iter_mut().rev() {
+            node.live = match node.write {
+                Some(x) => needed[x],
+                None => true,
+            };
+
+            if node.live {
+                for read in &node.reads {
+                    needed[*read] = true;
+                }
+            }
+        }
+    }
+
+    /// Returns the index of the last live pass that uses each target.
+    pub fn last_use(nodes: &[PassNode], target_count: usize) -> Vec<Option<usize>> {
+        let mut res = vec![None; target_count];
+        for (i, node) in nodes.iter().enumerate().filter(|x| x.1.live) {
+            for target in node.reads.iter().chain(&node.write) {
+                res[*target] = Some(i);
+            }
+        }
+
+        return res;
+    }
+
+    pub fn resolve_size(size: TargetSize, screen_res: uvec2) -> uvec2 {
+        return match size {
+            TargetSize::Scaled(factor) => uvec2(((screen_res.0 as f32 * factor) as u32).max(1), ((screen_res.1 as f32 * factor) as u32).max(1)),
+            TargetSize::Fixed(res) => res,
+        };
+    }
+
+    pub fn add_stats(total: &mut RenderStats, pass: &RenderStats) {
+        total.draw_calls += pass.draw_calls;
+        total.batch_draw_calls += pass.batch_draw_calls;
+        total.rt_draw_calls += pass.rt_draw_calls;
+        total.saved_draw_calls += pass.saved_draw_calls;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::graphics::post::GaussianBlur;
+
+    use super::{internal, Pass, RenderGraph, RenderGraphBuilder, RenderGraphError, TargetDesc};
+
+    fn build_err(builder: RenderGraphBuilder) -> RenderGraphError {
+        let Err(e) = builder.build() else { panic!("The graph should be invalid.") };
+        return e;
+    }
+
+    #[test]
+    fn validation() {
+        let err = build_err(RenderGraph::builder()
+            .target("a", TargetDesc::default())
+            .pass(Pass::effect("blur", GaussianBlur::default()).read("a")));
+        assert!(matches!(err, RenderGraphError::ReadBeforeWrite { pass, target } if pass == "blur" && target == "a"));
+
+        let err = build_err(RenderGraph::builder()
+            .target("a", TargetDesc::default())
+            .pass(Pass::scene("scene", 0).write("a"))
+            .pass(Pass::effect("blur", GaussianBlur::default()).read("a").write("a")));
+        assert!(matches!(err, RenderGraphError::ReadsOwnTarget { pass, target } if pass == "blur" && target == "a"));
+
+        let err = build_err(RenderGraph::builder()
+            .target("a", TargetDesc::default())
+            .target("a", TargetDesc::default()));
+        assert!(matches!(err, RenderGraphError::DuplicateTarget(x) if x == "a"));
+
+        let err = build_err(RenderGraph::builder().pass(Pass::effect("blur", GaussianBlur::default())));
+        assert!(matches!(err, RenderGraphError::InputCount { expected: 1, found: 0, .. }));
+    }
+
+    #[test]
+    fn culling_and_lifetimes() {
+        let graph = RenderGraph::builder()
+            .target("scene", TargetDesc::default())
+            .target("blurred", TargetDesc::default())
+            .target("debug", TargetDesc::default())
+            .pass(Pass::scene("scene", 0).write("scene"))
+            .pass(Pass::effect("blur", GaussianBlur::default()).read("scene").write("blurred"))
+            .pass(Pass::effect("debug_blur", GaussianBlur::default()).read("scene").write("debug"))
+            .pass(Pass::effect("final", GaussianBlur::default()).read("blurred"))
+            .build().unwrap_or_else(|e| panic!("{e}"));
+
+        // Nothing reads `debug`
+        assert_eq!(graph.nodes.iter().map(|x| x.live).collect::<Vec<_>>(), vec![true, true, false, true]);
+
+        // `scene` is released after `blur`, `blurred` after `final`, and `debug` is never used
+        assert_eq!(internal::last_use(&graph.nodes, graph.targets.len()), vec![Some(1), Some(3), None]);
+    }
+}
\ No newline at end of file
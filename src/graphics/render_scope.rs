@@ -2,13 +2,15 @@ use std::{f32::consts::PI, hint::unreachable_unchecked};
 
 use crate::{assert_expr, color::{Color, Color4}, graphics::{ui::text::precalc::LineSplit, Mode}, math::{mat3, quad::Quad, uvec2, vec2, Rect}, utils::ptr_slice::PtrSlice};
 
-use super::{CamData, material::Material, BlendingMode, batch::{BatchData, RefBatchState}, texture::{Texture, TextureFiltering}, DefaultMaterials, pipeline::{RenderPipeline, RenderTexture, SceneRenderData, DefaultRenderPipeline}, RenderStats, DEFAULT_CAM_DATA, ui::{UI_SINGLETON, UI, text::Text}};
+use super::{camera::{self, View}, CamData, material::Material, polyline::{self, LineStyle}, reflection, shader::Shader, BlendingMode, batch::{BatchData, ClipRect, MaskMode, RefBatchState}, texture::{Texture, TextureFiltering}, DefaultMaterials, pipeline::{RenderPipeline, RenderTexture, SceneRenderData, DefaultRenderPipeline}, RenderStats, DEFAULT_CAM_DATA, LayerSorting, ui::{UI_SINGLETON, UI, text::Text}};
 
 pub struct RenderScope {
     pub(super) is_global: bool,
 
     pub(super) cam_data: CamData,
     pub(super) cam_mat: mat3,
+    /// Extra cameras, with their matrices. When empty, the scene is rendered through `cam_mat` only.
+    views: Vec<(View, mat3)>,
     
     pub(super) pixels_per_unit: f32,
     pub(super) pivot: vec2,
@@ -20,6 +22,21 @@ pub struct RenderScope {
     tex_material: Option<Material>,
     ellipse_material: Option<Material>,
     custom_material: Option<Material>,
+    instanced_material: Option<Material>,
+    multi_tex_material: Option<Material>,
+
+    pub(super) instancing: bool,
+    pub(super) multi_texturing: bool,
+
+    pub(super) layer: i32,
+    pub(super) sort_key: f32,
+
+    pub(super) mask: MaskMode,
+    mask_id: u32,
+    clip_stack: Vec<ClipRect>,
+
+    /// Shader, shader generation and vertex layout last checked by `draw_custom_mesh`.
+    validated_layout: Option<(Shader, u32, Box<[usize]>)>,
 
     pub(super) render_target: u8,
     pub(super) clear_col: Color4,
@@ -32,8 +49,12 @@ impl RenderScope {
     pub(super) const fn new_global() -> Self {
         Self {
             is_global: true,
-            cam_data: DEFAULT_CAM_DATA, cam_mat: mat3::IDENTITY, pixels_per_unit: 1.0, pivot: vec2::ZERO, snapping: None,
-            line_material: None, rect_material: None, tex_material: None, ellipse_material: None, custom_material: None,
+            cam_data: DEFAULT_CAM_DATA, cam_mat: mat3::IDENTITY, views: Vec::new(), pixels_per_unit: 1.0, pivot: vec2::ZERO, snapping: None,
+            line_material: None, rect_material: None, tex_material: None, ellipse_material: None, custom_material: None, instanced_material: None, multi_tex_material: None,
+            instancing: false, multi_texturing: false,
+            layer: 0, sort_key: 0.0,
+            mask: MaskMode::Disabled, mask_id: 0, clip_stack: Vec::new(),
+            validated_layout: None,
             render_target: 0, clear_col: Color4::BLACK, blending: BlendingMode::AlphaMix,
             batch_data: BatchData::new(),
         }
@@ -42,8 +63,12 @@ impl RenderScope {
     pub const fn new() -> Self {
         Self {
             is_global: false,
-            cam_data: DEFAULT_CAM_DATA, cam_mat: mat3::IDENTITY, pixels_per_unit: 1.0, pivot: vec2::ZERO, snapping: None,
-            line_material: None, rect_material: None, tex_material: None, ellipse_material: None, custom_material: None,
+            cam_data: DEFAULT_CAM_DATA, cam_mat: mat3::IDENTITY, views: Vec::new(), pixels_per_unit: 1.0, pivot: vec2::ZERO, snapping: None,
+            line_material: None, rect_material: None, tex_material: None, ellipse_material: None, custom_material: None, instanced_material: None, multi_tex_material: None,
+            instancing: false, multi_texturing: false,
+            layer: 0, sort_key: 0.0,
+            mask: MaskMode::Disabled, mask_id: 0, clip_stack: Vec::new(),
+            validated_layout: None,
             render_target: 0, clear_col: Color4::BLACK, blending: BlendingMode::AlphaMix,
             batch_data: BatchData::new()
         }
@@ -83,6 +108,32 @@ impl RenderScope {
         return stats;
     }
 
+    /// Starts writing a new mask. Anything drawn until `end_mask` writes into the mask instead of the color buffer.<br>
+    /// - Textures with an alpha below 0.5 are discarded from the mask.
+    /// - Masks are applied in submission order, so they require `LayerSorting::Submission`. Other sortings may move the draws that write a mask after the ones clipped by it.
+    pub fn begin_mask(&mut self) {
+        self.begin_mask_ext(0.5);
+    }
+
+    /// Starts writing a new mask. Anything drawn until `end_mask` writes into the mask instead of the color buffer.<br>
+    /// - Textures with an alpha below `alpha_cutoff` are discarded from the mask.
+    /// - Masks are applied in submission order, so they require `LayerSorting::Submission`. Other sortings may move the draws that write a mask after the ones clipped by it.
+    pub fn begin_mask_ext(&mut self, alpha_cutoff: f32) {
+        self.mask_id = self.mask_id.wrapping_add(1);
+        self.mask = MaskMode::Write { id: self.mask_id, alpha_cutoff };
+    }
+
+    /// Stops writing the mask. Anything drawn until `clear_mask` is clipped by it.
+    pub fn end_mask(&mut self) {
+        assert_expr!(!matches!(self.mask, MaskMode::Disabled), "No mask has been started.");
+        self.mask = MaskMode::Test;
+    }
+
+    /// Disables the mask. Following draws won't be clipped.
+    pub fn clear_mask(&mut self) {
+        self.mask = MaskMode::Disabled;
+    }
+
     
     const RECT_TRIS: [u32; 6] = [0, 1, 2, 2, 3, 0];
     pub(super) fn draw_rect(&mut self, pos: vec2, extents: vec2, rot: f32, colors: [Color4; 4]) -> Quad {
@@ -110,9 +161,35 @@ impl RenderScope {
 
         let tf_mat = mat3::transform_matrix(pos, rot, extents);
         let quad = internal::make_quad(self.pivot, &tf_mat, self.snapping.as_ref());
-        let vert_data = [Vert(quad.ld, colors[0], uvs.lu()), Vert(quad.lu, colors[1], uvs.ld()), Vert(quad.ru, colors[2], uvs.rd()), Vert(quad.rd, colors[3], uvs.ru())];
-
         let textures = &[tex];
+
+        if self.instancing && colors.iter().all(|x| *x == colors[0]) {
+            #[repr(C)]
+            struct Instance(vec2, vec2, vec2, vec2, vec2, Color4);
+
+            let instance = [Instance(quad.ld, quad.rd - quad.ld, quad.lu - quad.ld, uvs.lu(), uvs.rd(), colors[0])];
+            let instance_data = internal::convert_vert_data(&instance);
+
+            let state = self.gen_ref_state(Mode::Instanced, &[2, 2, 2, 2, 2, 4], textures);
+            self.batch_data.send(self.render_target, state, instance_data, &[]);
+
+            return internal::fix_quad(quad);
+        }
+
+        if self.multi_texturing {
+            #[repr(C)]
+            struct SlotVert(vec2, Color4, vec2, f32); // Slot is written when batched
+
+            let vert_data = [SlotVert(quad.ld, colors[0], uvs.lu(), 0.0), SlotVert(quad.lu, colors[1], uvs.ld(), 0.0), SlotVert(quad.ru, colors[2], uvs.rd(), 0.0), SlotVert(quad.rd, colors[3], uvs.ru(), 0.0)];
+            let vert_data = internal::convert_vert_data(&vert_data);
+
+            let state = self.gen_ref_state(Mode::MultiTextured, &[2, 4, 2, 1], textures);
+            self.batch_data.send(self.render_target, state, vert_data, &Self::RECT_TRIS);
+
+            return internal::fix_quad(quad);
+        }
+
+        let vert_data = [Vert(quad.ld, colors[0], uvs.lu()), Vert(quad.lu, colors[1], uvs.ld()), Vert(quad.ru, colors[2], uvs.rd()), Vert(quad.rd, colors[3], uvs.ru())];
         let vert_data = internal::convert_vert_data(&vert_data);
         
         let state = self.gen_ref_state(Mode::Textured, &[2, 4, 2], textures);
@@ -190,9 +267,26 @@ impl RenderScope {
         self.batch_data.send(self.render_target, state, vert_data, &Self::LINE_TRIS);
     }
 
+    pub(super) fn draw_polyline(&mut self, points: &[vec2], style: &LineStyle, colors: &[Color4]) {
+        let points = points.iter().map(|x| {
+            let p = vec2(x.0, -x.1);
+            if let Some(s) = &self.snapping { s.snap(p) } else { p }
+        }).collect::<Vec<_>>();
+
+        let (vert_data, tri_data) = polyline::tessellate(&points, colors, style);
+        if tri_data.is_empty() {
+            return;
+        }
+
+        let state = self.gen_ref_state(Mode::Rect, &[2, 4], &[]);
+        self.batch_data.send(self.render_target, state, &vert_data, &tri_data);
+    }
+
     pub(super) unsafe fn draw_custom_mesh(&mut self, pos: vec2, rot: f32, scale: vec2, vert_data: &[f32], tri_data: &[u32], vert_attribs: &[usize], textures: &[&Texture]) {
         assert_expr!(tri_data.len() % 3 == 0, "The number of indices must be a multiple of 3.");
 
+        self.validate_layout(vert_attribs);
+
         let tf_mat = mat3::transform_matrix(pos, rot, scale);
 
         let stride = vert_attribs.iter().sum();
@@ -209,6 +303,21 @@ impl RenderScope {
         self.batch_data.send(self.render_target, state, &vert_data, tri_data);
     }
 
+    /// Checks the vertex layout against the custom shader, only if any of them changed since the last check.
+    fn validate_layout(&mut self, vert_attribs: &[usize]) {
+        let Some(material) = self.get_material(Mode::Custom) else { return };
+        let shader = material.shader();
+
+        if let Some((last_shader, last_gen, last_attribs)) = &self.validated_layout {
+            if last_shader == shader && *last_gen == shader.generation() && last_attribs.as_ref() == vert_attribs {
+                return;
+            }
+        }
+
+        reflection::validate_attribs(shader, vert_attribs);
+        self.validated_layout = Some((shader.clone(), shader.generation(), vert_attribs.into()));
+    }
+
     pub(super) fn draw_text<T>(&mut self, text: &Text<'_, T>) -> (Quad, Option<()>) {
         assert_expr!(text.font.is_some(), "A font was not provided!");
 
@@ -301,6 +410,8 @@ impl RenderScope {
             Mode::Textured => self.tex_material = material,
             Mode::Ellipse => self.ellipse_material = material,
             Mode::Custom => self.custom_material = material,
+            Mode::Instanced => self.instanced_material = material,
+            Mode::MultiTextured => self.multi_tex_material = material,
         }
     }
 
@@ -314,6 +425,8 @@ impl RenderScope {
             Mode::Textured => Some(self.tex_material.clone().unwrap_or(DefaultMaterials::def_tex_material())),
             Mode::Ellipse => Some(self.ellipse_material.clone().unwrap_or(DefaultMaterials::def_ellipse_material())),
             Mode::Custom => self.custom_material.clone(),
+            Mode::Instanced => Some(self.instanced_material.clone().unwrap_or(DefaultMaterials::def_instanced_material())),
+            Mode::MultiTextured => Some(self.multi_tex_material.clone().unwrap_or(DefaultMaterials::def_multi_tex_material())),
         };
     }
 
@@ -323,6 +436,7 @@ impl RenderScope {
             draw_calls: 0,
             batch_draw_calls: 0,
             rt_draw_calls: 0,
+            saved_draw_calls: 0,
         };
 
         if include_ui && UI::is_enabled() {
@@ -346,14 +460,66 @@ impl RenderScope {
     pub(super) fn set_camera(&mut self, cam_data: CamData) {
         assert_expr!(cam_data.half_size.0 != 0.0 && cam_data.half_size.1 != 0.0, "The size of the camera must be a vector with non-zero components.");
 
+        self.cam_data = cam_data;
+        self.cam_mat = self.cam_matrix(&cam_data);
+    }
+
+    pub(super) fn set_views(&mut self, views: &[View]) {
+        self.views = views.iter().map(|x| (*x, self.cam_matrix(&x.cam()))).collect();
+    }
+
+    pub(super) fn get_views(&self) -> Vec<View> {
+        return self.views.iter().map(|x| x.0).collect();
+    }
+
+    /// Converts a position in pixels, with the origin at the top left, to world space through the topmost view under it.<br>
+    /// Returns the index of the view and the world position, or `None` if there are no views under the position.
+    pub fn view_screen_to_world(&self, screen_pos: vec2, screen_res: uvec2) -> Option<(usize, vec2)> {
+        let (index, (view, mat)) = self.views.iter().enumerate().rev().find(|(_, (view, _))| view.contains(screen_pos, screen_res))?;
+        return Some((index, camera::normalized_to_world(mat, view.local_pos(screen_pos, screen_res))));
+    }
+
+    fn cam_matrix(&self, cam_data: &CamData) -> mat3 {
         let cam_pos = if let Some(x) = &self.snapping {
             if x.apply_to_cam { x.snap(cam_data.pos) } else { cam_data.pos }
         } else {
             cam_data.pos
         };
 
-        self.cam_data = cam_data;
-        self.cam_mat = mat3::cam_matrix(cam_pos, cam_data.half_size);
+        return mat3::cam_matrix_ext(cam_pos, cam_data.half_size, cam_data.rot);
+    }
+
+    /// Converts a position in pixels, with the origin at the top left, to world space through the camera matrix of this scope.
+    pub fn screen_to_world(&self, screen_pos: vec2, screen_res: uvec2) -> vec2 {
+        return camera::screen_to_world(&self.cam_mat, screen_pos, screen_res);
+    }
+
+    /// Converts a world position to pixels, with the origin at the top left, through the camera matrix of this scope.
+    pub fn world_to_screen(&self, world_pos: vec2, screen_res: uvec2) -> vec2 {
+        return camera::world_to_screen(&self.cam_mat, world_pos, screen_res);
+    }
+
+    pub(super) fn set_layer_sorting(&mut self, layer: i32, sorting: LayerSorting) {
+        self.batch_data.set_sorting(layer, sorting);
+    }
+
+    pub(super) fn get_layer_sorting(&self, layer: i32) -> LayerSorting {
+        return self.batch_data.get_sorting(layer);
+    }
+
+    /// Pushes a clip rect, intersected with the current one.
+    pub(super) fn push_clip(&mut self, clip: ClipRect) {
+        let clip = match self.clip_stack.last() {
+            Some(x) => x.intersect(&clip),
+            None => clip,
+        };
+
+        self.clip_stack.push(clip);
+    }
+
+    pub(super) fn pop_clip(&mut self) {
+        let popped = self.clip_stack.pop();
+        assert_expr!(popped.is_some(), "No clip rect to pop.");
     }
 
     pub(super) fn set_snapping(&mut self, snapping: Option<Snapping>) {
@@ -361,6 +527,7 @@ impl RenderScope {
         self.snapping = snapping;
 
         self.set_camera(self.cam_data);
+        self.set_views(&self.get_views());
     }
 
     fn gen_ref_state<'a>(&'a self, mode: Mode, attribs: &'a [usize], textures: &'a [&'a Texture]) -> RefBatchState {
@@ -372,11 +539,17 @@ impl RenderScope {
             textures: unsafe { PtrSlice::from(textures).pointerify() },
             blending: self.blending,
             is_line: matches!(mode, Mode::Line),
+            instanced: matches!(mode, Mode::Instanced),
+            multi_texture: matches!(mode, Mode::MultiTextured),
+            layer: self.layer,
+            sort_key: self.sort_key,
+            mask: self.mask,
+            clip: self.clip_stack.last().copied(),
         };
     }
 
     fn gen_scene_data(&self) -> SceneRenderData<'_> {
-        SceneRenderData { products: self.batch_data.targets.as_slice(), clear_col: self.clear_col, cam: &self.cam_mat }
+        SceneRenderData { products: self.batch_data.targets.as_slice(), clear_col: self.clear_col, cam: &self.cam_mat, views: &self.views }
     }
 }
 
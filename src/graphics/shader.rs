@@ -1,23 +1,44 @@
-use std::{fmt::Display, ffi::{CString, NulError}, sync::Arc};
+use std::{fmt::Display, ffi::{CString, NulError}, path::Path, sync::{Arc, Weak}};
 
 use thiserror::Error;
 
 use crate::{Res, assert_expr};
 
-use super::{gl_bindings::{program::GlProgram, shader::{GlShader, GlShaderType}}, DefaultShaders};
+use super::{consts::{GLOBAL_BLOCK_BINDING, GLOBAL_BLOCK_NAME}, gl_bindings::{program::GlProgram, shader::{GlShader, GlShaderType}}, gl_call, preprocessor::{preprocess, BuiltinProvider, LineOrigin, SourceProvider}, diagnostics::{self, ShaderDiagnostic}, hot_reload, DefaultShaders};
 
 #[derive(Debug, Error)]
 pub enum ShaderError {
     #[error("{0}")]
     NulError(#[from] NulError),
-    #[error("{kind} Shader Compilation Error:\n{msg}")]
-    CompilationError { kind: SubShaderType, msg: String },
-    #[error("Shader Linking Error:\n{msg}")]
-    LinkingError { msg: String },
+    #[error("{0}")]
+    IoError(#[from] std::io::Error),
+    #[error("{kind} Shader Compilation Error:\n{}", diagnostics::pretty(diagnostics, msg))]
+    CompilationError { kind: SubShaderType, msg: String, diagnostics: Vec<ShaderDiagnostic> },
+    #[error("Shader Linking Error:\n{}", diagnostics::pretty(diagnostics, msg))]
+    LinkingError { msg: String, diagnostics: Vec<ShaderDiagnostic> },
+    #[error("Include Error ('{path}'): {msg}")]
+    IncludeError { path: String, msg: String },
+}
+
+impl ShaderError {
+    /// Returns the parsed driver diagnostics, if any.
+    pub fn diagnostics(&self) -> &[ShaderDiagnostic] {
+        return match self {
+            ShaderError::CompilationError { diagnostics, .. } | ShaderError::LinkingError { diagnostics, .. } => diagnostics,
+            _ => &[],
+        };
+    }
+
+    pub(super) fn map_lines(mut self, lines: &[LineOrigin]) -> Self {
+        if let ShaderError::CompilationError { diagnostics, .. } = &mut self {
+            diagnostics::map_lines(diagnostics, lines);
+        }
+        return self;
+    }
 }
 
 #[repr(u32)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SubShaderType {
     Vert = GlShaderType::Vert as u32,
     Frag = GlShaderType::Frag as u32,
@@ -46,10 +67,18 @@ impl SubShader {
     
     /// Compiles the sub shader.
     pub fn new(src: &str, kind: SubShaderType) -> Res<Self, ShaderError> {
-        let core = GlShader::new(src, kind.into())?;
+        let core = GlShader::new(src, kind)?;
         return Ok(Self { core: Some(Arc::new(core)), kind })
     }
 
+    /// Preprocesses and compiles the sub shader.<br>
+    /// - `#include "file"` directives are resolved through `provider`.
+    /// - `defines` are injected after the `#version` directive.
+    pub fn preprocessed(src: &str, kind: SubShaderType, defines: &[(&str, &str)], provider: &dyn SourceProvider) -> Res<Self, ShaderError> {
+        let processed = preprocess(src, defines, provider)?;
+        return Self::new(&processed.source, kind).map_err(|e| e.map_lines(&processed.lines));
+    }
+
     fn gl_shader(&self) -> &GlShader {
         self.core.as_ref().unwrap()
     }
@@ -57,11 +86,23 @@ impl SubShader {
 
 
 /// The main component of the shading pipeline. It defines how geometry must be drawn.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct Shader {
     core: Option<Arc<GlProgram>>
 }
 
+impl PartialEq for Shader {
+    fn eq(&self, other: &Self) -> bool {
+        return match (&self.core, &other.core) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+    }
+}
+
+impl Eq for Shader {}
+
 impl Shader {
     pub(super) const fn invalid() -> Self {
         Self { core: None }
@@ -73,20 +114,81 @@ impl Shader {
         assert_expr!(matches!(frag.kind, SubShaderType::Frag), "The fragment shader must be a fragment shader.");
 
         let core = GlProgram::new(vert.gl_shader(), frag.gl_shader())?;
-        return Ok(Self { core: Some(Arc::new(core)) });
+        let shader = Self { core: Some(Arc::new(core)) };
+        shader.bind_block(GLOBAL_BLOCK_NAME, GLOBAL_BLOCK_BINDING);
+
+        return Ok(shader);
     }
 
     /// Creates a blit shader from a src.
+    /// - Builtin includes, like `nogine/blit.glsl`, are available.
     pub fn new_blit(src: &str) -> Res<Self, ShaderError> {
-        let frag = SubShader::new(src, SubShaderType::Frag)?;
+        let frag = SubShader::preprocessed(src, SubShaderType::Frag, &[], &BuiltinProvider)?;
         return Self::new(&DefaultShaders::def_blit_vert(), &frag);
     }
 
+    /// Loads a shader from a vertex and a fragment file.<br>
+    /// - `#include` directives are resolved relative to each file.
+    /// - If hot reload is enabled, the shader is recompiled when any of its files change.
+    pub fn from_files(vert_path: impl AsRef<Path>, frag_path: impl AsRef<Path>) -> Res<Self, ShaderError> {
+        let (vert_path, frag_path) = (vert_path.as_ref(), frag_path.as_ref());
+
+        let (shader, files) = hot_reload::compile(vert_path, frag_path)?;
+        hot_reload::watch(&shader, vert_path, frag_path, files);
+
+        return Ok(shader);
+    }
+
+    /// Enables or disables hot reloading for shaders created with `from_files`.<br>
+    /// - When a file changes, the shader is recompiled and swapped in place, so every `Material` using it is updated.
+    /// - If compilation fails, the error is logged and the previous version is kept.
+    pub fn set_hot_reload(enabled: bool) {
+        hot_reload::set_enabled(enabled);
+    }
+
+    /// Returns if hot reloading is enabled.
+    pub fn is_hot_reload() -> bool {
+        return hot_reload::is_enabled();
+    }
+
+    /// Binds the uniform block `name` to the binding point of a `UniformBlock`.<br>
+    /// - The `Globals` block is bound automatically.
+    /// - Returns `false` if the shader doesn't declare the block.
+    pub fn bind_block(&self, name: &str, binding: u32) -> bool {
+        let Ok(name) = CString::new(name) else { return false };
+
+        let index = gl_call!(gl::GetUniformBlockIndex(self.id(), name.as_ptr()));
+        if index == gl::INVALID_INDEX {
+            return false;
+        }
+
+        gl_call!(gl::UniformBlockBinding(self.id(), index, binding));
+        return true;
+    }
+
     pub(super) fn enable(&self) {
         self.gl_program().enable();
     }
 
-    fn gl_program(&self) -> &GlProgram {
+    pub(super) fn id(&self) -> u32 {
+        self.gl_program().id()
+    }
+
+    /// Number of times the shader has been hot reloaded.
+    pub(super) fn generation(&self) -> u32 {
+        self.gl_program().generation()
+    }
+
+    pub(super) fn downgrade(&self) -> Weak<GlProgram> {
+        Arc::downgrade(self.core.as_ref().unwrap())
+    }
+
+    /// Takes the program of a freshly created shader.
+    pub(super) fn into_program(self) -> GlProgram {
+        return Arc::into_inner(self.core.unwrap()).unwrap();
+    }
+
+    pub(super) fn gl_program(&self) -> &GlProgram {
         self.core.as_ref().unwrap()
     }
 }
@@ -1,11 +1,11 @@
-use std::{io::{Read, Seek, BufReader}, sync::Arc};
+use std::{borrow::Cow, io::{Read, Seek, BufReader}, path::Path, sync::Arc};
 
 use image::{EncodableLayout, GenericImageView, ImageError};
 use thiserror::Error;
 
 use crate::{assert_expr, color::BColor4, math::{uvec2, vec2, Rect}, Res};
 
-use super::super::gl_call;
+use super::super::{gl_bindings::gl_with_pack_alignment, gl_call};
 
 pub mod atlasgen;
 
@@ -21,7 +21,7 @@ pub enum TextureError {
 
 /// Defines how a texture is scaled.
 #[repr(u32)]
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TextureFiltering {
     Closest = gl::NEAREST,
     Linear = gl::LINEAR
@@ -149,10 +149,29 @@ impl Texture {
     pub fn with_pixels<T, F: Fn(Pixels<'_>) -> T>(&self, func: F) -> Option<T> {
         let x = &self.data.as_ref()?[..];
 
-        let px = Pixels { inner: x, res: self.dims };
+        let px = Pixels { inner: Cow::Borrowed(x), res: self.dims };
         return Some(func(px));
     }
 
+    /// Downloads the texture data from the GPU as RGBA.<br>
+    /// - Works even if the data was removed from RAM.
+    /// - Rows are in the same order as the data provided to `Texture::new`.
+    /// - Textures statified from HDR render textures are clamped to `[0, 1]`.
+    pub fn download(&self) -> Pixels<'static> {
+        let mut data = vec![0u8; (self.dims.0 * self.dims.1 * 4) as usize];
+
+        gl_call!(gl::BindTexture(gl::TEXTURE_2D, self.id.0));
+        gl_with_pack_alignment(1, || gl_call!(gl::GetTexImage(gl::TEXTURE_2D, 0, gl::RGBA, gl::UNSIGNED_BYTE, data.as_mut_ptr() as *mut std::ffi::c_void)));
+        gl_call!(gl::BindTexture(gl::TEXTURE_2D, 0));
+
+        return Pixels::owned(data.into(), self.dims);
+    }
+
+    /// Downloads the texture data and saves it as a png.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Res<(), TextureError> {
+        return self.download().save_png(path);
+    }
+
     /// Removes the texture data from RAM.
     pub fn invalidate_data(&mut self) {
         self.data = None;
@@ -289,11 +308,19 @@ pub(crate) fn load_texture_data(src: impl Read + Seek, cfg: TextureCfg) -> Res<R
         return Ok(RawTexData { data, fmt, dims, cfg });
 }
 
+/// A view of RGBA pixel data, either borrowed from a texture or owned after a GPU readback.
 pub struct Pixels<'a> {
-    inner: &'a [u8],
+    inner: Cow<'a, [u8]>,
     res: uvec2,
 }
 
+impl Pixels<'static> {
+    pub(crate) fn owned(data: Box<[u8]>, res: uvec2) -> Self {
+        assert_expr!(data.len() == (res.0 * res.1 * 4) as usize, "Pixel data doesn't match the resolution.");
+        return Self { inner: Cow::Owned(data.into_vec()), res };
+    }
+}
+
 impl<'a> Pixels<'a> {
     pub fn get(&self, pos: uvec2) -> BColor4 {
         assert_expr!(pos.0 < self.res.0 && pos.1 < self.res.1, "Pixel out of bounds! (Pos was ({}, {}), Res was ({}, {}))", pos.0, pos.1, self.res.0, self.res.1);
@@ -305,6 +332,22 @@ impl<'a> Pixels<'a> {
     pub fn res(&self) -> uvec2 {
         self.res
     }
+
+    /// Returns the raw RGBA bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.inner
+    }
+
+    /// Takes ownership of the raw RGBA bytes.
+    pub fn into_bytes(self) -> Box<[u8]> {
+        return self.inner.into_owned().into_boxed_slice();
+    }
+
+    /// Saves the pixels as a png.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Res<(), TextureError> {
+        image::save_buffer_with_format(path, &self.inner, self.res.0, self.res.1, image::ColorType::Rgba8, image::ImageFormat::Png)?;
+        return Ok(());
+    }
 }
 
 
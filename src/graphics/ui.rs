@@ -4,7 +4,7 @@ use crate::{assert_expr, color::{Color, Color4}, graphics::{render_scope::Snappi
 
 use self::{internal::ActiveData, text::{Text, SourcedFromUI}};
 
-use super::{render_scope::RenderScope, texture::{Texture, Sprite}};
+use super::{batch::ClipRect, polyline::LineStyle, render_scope::RenderScope, texture::{Texture, Sprite}};
 
 macro_rules! assert_ui_enabled {
     () => {
@@ -262,6 +262,15 @@ impl UI {
         writer.scope.draw_line(rd, ld, [color; 2]);
     }
 
+    /// Draws a thick line through `points`, like `Graphics::draw_polyline_ext`. Useful for graphs.<br>
+    /// - `style.width` is in UI units.
+    pub fn draw_polyline(points: &[vec2], style: &LineStyle, colors: &[Color4]) {
+        assert_ui_enabled!();
+
+        let points = points.iter().map(|x| vec2(x.0, -x.1)).collect::<Vec<_>>();
+        UI_SINGLETON.write().unwrap().scope.draw_polyline(&points, style, colors);
+    }
+
     /// Handles interaction with a rect.<br>
     /// - `id` should be tried to be kept unique, at least to the extent where no interactables have the same id at the same time.
     pub fn interactable(rect: Rect, id: impl Into<String>) -> Option<Interaction> {
@@ -276,6 +285,19 @@ impl UI {
         return input;
     }
 
+    /// Clips following UI draws to `rect`, intersected with the current clip.<br>
+    /// - Must be matched with a call to `pop_clip_rect`.
+    pub fn push_clip_rect(rect: Rect) {
+        assert_ui_enabled!();
+        UI_SINGLETON.write().unwrap().scope.push_clip(ClipRect::from_points(&[rect.start, rect.end]));
+    }
+
+    /// Restores the previous clip rect.
+    pub fn pop_clip_rect() {
+        assert_ui_enabled!();
+        UI_SINGLETON.write().unwrap().scope.pop_clip();
+    }
+
     /// Sets the UI tint.
     pub fn set_tint(tint: Color4) {
         assert_ui_enabled!();
@@ -293,7 +315,7 @@ impl UI {
         assert_ui_enabled!();
         
         let half_size = vec2(res.0 as f32, res.1 as f32) * 0.5;
-        UI_SINGLETON.write().unwrap().scope.set_camera(CamData { pos: vec2(half_size.0, -half_size.1), half_size });
+        UI_SINGLETON.write().unwrap().scope.set_camera(CamData { pos: vec2(half_size.0, -half_size.1), half_size, rot: 0.0 });
     }
 
     /// Returns the UI resolution.
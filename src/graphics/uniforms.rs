@@ -1,4 +1,10 @@
-//use super::texture::Texture;
+use std::sync::RwLock;
+
+use crate::{crash, math::{mat3, uvec2}};
+
+use super::{consts::GLOBAL_BLOCK_BINDING, gl_bindings::buffer::{GlBuffer, GlBufferKind, GlBufferUsage}, gl_call, texture::Texture};
+
+static GLOBAL_BLOCK: RwLock<GlobalBlock> = RwLock::new(GlobalBlock::new());
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Uniform {
@@ -7,6 +13,189 @@ pub enum Uniform {
     Float3(f32, f32, f32),
     Float4(f32, f32, f32, f32),
     Int(i32),
+    Int2(i32, i32),
+    Int3(i32, i32, i32),
+    Int4(i32, i32, i32, i32),
     Uint(u32),
-    //Texture(Texture),
-}
\ No newline at end of file
+    Uint2(u32, u32),
+    Uint3(u32, u32, u32),
+    Uint4(u32, u32, u32, u32),
+    Bool(bool),
+    /// Row-major.
+    Mat2([[f32; 2]; 2]),
+    /// Row-major.
+    Mat3([[f32; 3]; 3]),
+    /// Row-major.
+    Mat4([[f32; 4]; 4]),
+    /// Bound to the first free texture slot after the batch textures.
+    Texture(Texture),
+    /// Array of uniforms, all elements must be of the same kind.<br>
+    /// - Textures and nested arrays are not supported.
+    Array(Vec<Uniform>),
+}
+
+impl Uniform {
+    pub(super) fn upload(&self, location: i32) {
+        match self {
+            Uniform::Float(x) => gl_call!(gl::Uniform1f(location, *x)),
+            Uniform::Float2(x, y) => gl_call!(gl::Uniform2f(location, *x, *y)),
+            Uniform::Float3(x, y, z) => gl_call!(gl::Uniform3f(location, *x, *y, *z)),
+            Uniform::Float4(x, y, z, w) => gl_call!(gl::Uniform4f(location, *x, *y, *z ,*w)),
+            Uniform::Int(x) => gl_call!(gl::Uniform1i(location, *x)),
+            Uniform::Int2(x, y) => gl_call!(gl::Uniform2i(location, *x, *y)),
+            Uniform::Int3(x, y, z) => gl_call!(gl::Uniform3i(location, *x, *y, *z)),
+            Uniform::Int4(x, y, z, w) => gl_call!(gl::Uniform4i(location, *x, *y, *z, *w)),
+            Uniform::Uint(x) => gl_call!(gl::Uniform1ui(location, *x)),
+            Uniform::Uint2(x, y) => gl_call!(gl::Uniform2ui(location, *x, *y)),
+            Uniform::Uint3(x, y, z) => gl_call!(gl::Uniform3ui(location, *x, *y, *z)),
+            Uniform::Uint4(x, y, z, w) => gl_call!(gl::Uniform4ui(location, *x, *y, *z, *w)),
+            Uniform::Bool(x) => gl_call!(gl::Uniform1i(location, *x as i32)),
+            Uniform::Mat2(x) => gl_call!(gl::UniformMatrix2fv(location, 1, gl::TRUE, x.as_ptr() as *const f32)),
+            Uniform::Mat3(x) => gl_call!(gl::UniformMatrix3fv(location, 1, gl::TRUE, x.as_ptr() as *const f32)),
+            Uniform::Mat4(x) => gl_call!(gl::UniformMatrix4fv(location, 1, gl::TRUE, x.as_ptr() as *const f32)),
+            Uniform::Texture(_) => crash!("Texture uniforms must be bound through the material."),
+            Uniform::Array(x) => internal::upload_array(location, x),
+        }
+    }
+}
+
+
+/// A uniform buffer object shared between shaders.<br>
+/// - Shaders access it through a uniform block bound with `Shader::bind_block`.
+/// - The data must follow the `std140` layout of the block.
+pub struct UniformBlock {
+    buffer: GlBuffer,
+    binding: u32,
+}
+
+impl UniformBlock {
+    /// Creates a block of `size` bytes attached to the binding point `binding`.
+    pub fn new(binding: u32, size: usize) -> Self {
+        let buffer = GlBuffer::prealloc(size, GlBufferKind::UBO, GlBufferUsage::DynamicDraw);
+        buffer.bind_base(binding);
+
+        return Self { buffer, binding };
+    }
+
+    /// Uploads `data` into the block.
+    pub fn set<T: Copy>(&self, data: &T) {
+        self.buffer.subdata(std::slice::from_ref(data), 0);
+    }
+
+    /// Uploads `data` into the block at `byte_offset`.
+    pub fn set_at<T: Copy>(&self, data: &[T], byte_offset: usize) {
+        self.buffer.subdata(data, byte_offset);
+    }
+
+    pub fn binding(&self) -> u32 {
+        self.binding
+    }
+}
+
+
+/// State of the per-frame `Globals` block.
+pub(super) struct GlobalBlock {
+    block: Option<UniformBlock>,
+    time: f32,
+    delta_time: f32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct GlobalBlockData {
+    cam: [[f32; 4]; 3],
+    resolution: [f32; 2],
+    time: f32,
+    delta_time: f32,
+}
+
+impl GlobalBlock {
+    const fn new() -> Self {
+        Self { block: None, time: 0.0, delta_time: 0.0 }
+    }
+
+    pub(super) fn init() {
+        GLOBAL_BLOCK.write().unwrap().block = Some(UniformBlock::new(GLOBAL_BLOCK_BINDING, std::mem::size_of::<GlobalBlockData>()));
+    }
+
+    pub(super) fn advance(ts: f32) {
+        let mut writer = GLOBAL_BLOCK.write().unwrap();
+        writer.time += ts;
+        writer.delta_time = ts;
+    }
+
+    /// Uploads the camera and resolution of the current render.
+    pub(super) fn upload(cam: &mat3, res: uvec2) {
+        let reader = GLOBAL_BLOCK.read().unwrap();
+        let Some(block) = &reader.block else { return };
+
+        let rows = cam.rows();
+        let col = |i: usize| [rows[0][i], rows[1][i], rows[2][i], 0.0];
+
+        block.set(&GlobalBlockData {
+            cam: [col(0), col(1), col(2)],
+            resolution: [res.0 as f32, res.1 as f32],
+            time: reader.time,
+            delta_time: reader.delta_time,
+        });
+    }
+}
+
+mod internal {
+    use crate::{crash, graphics::gl_call};
+
+    use super::Uniform;
+
+    pub fn upload_array(location: i32, items: &[Uniform]) {
+        let Some(first) = items.first() else { return };
+        if items.iter().any(|x| std::mem::discriminant(x) != std::mem::discriminant(first)) {
+            crash!("All the elements of a uniform array must be of the same kind.");
+        }
+
+        let count = items.len() as i32;
+        let floats = || items.iter().flat_map(|x| match x {
+            Uniform::Float(x) => vec![*x],
+            Uniform::Float2(x, y) => vec![*x, *y],
+            Uniform::Float3(x, y, z) => vec![*x, *y, *z],
+            Uniform::Float4(x, y, z, w) => vec![*x, *y, *z, *w],
+            Uniform::Mat2(x) => x.iter().flatten().copied().collect(),
+            Uniform::Mat3(x) => x.iter().flatten().copied().collect(),
+            Uniform::Mat4(x) => x.iter().flatten().copied().collect(),
+            _ => unreachable!(),
+        }).collect::<Vec<f32>>();
+        let ints = || items.iter().flat_map(|x| match x {
+            Uniform::Int(x) => vec![*x],
+            Uniform::Int2(x, y) => vec![*x, *y],
+            Uniform::Int3(x, y, z) => vec![*x, *y, *z],
+            Uniform::Int4(x, y, z, w) => vec![*x, *y, *z, *w],
+            Uniform::Bool(x) => vec![*x as i32],
+            _ => unreachable!(),
+        }).collect::<Vec<i32>>();
+        let uints = || items.iter().flat_map(|x| match x {
+            Uniform::Uint(x) => vec![*x],
+            Uniform::Uint2(x, y) => vec![*x, *y],
+            Uniform::Uint3(x, y, z) => vec![*x, *y, *z],
+            Uniform::Uint4(x, y, z, w) => vec![*x, *y, *z, *w],
+            _ => unreachable!(),
+        }).collect::<Vec<u32>>();
+
+        match first {
+            Uniform::Float(_) => gl_call!(gl::Uniform1fv(location, count, floats().as_ptr())),
+            Uniform::Float2(..) => gl_call!(gl::Uniform2fv(location, count, floats().as_ptr())),
+            Uniform::Float3(..) => gl_call!(gl::Uniform3fv(location, count, floats().as_ptr())),
+            Uniform::Float4(..) => gl_call!(gl::Uniform4fv(location, count, floats().as_ptr())),
+            Uniform::Int(_) | Uniform::Bool(_) => gl_call!(gl::Uniform1iv(location, count, ints().as_ptr())),
+            Uniform::Int2(..) => gl_call!(gl::Uniform2iv(location, count, ints().as_ptr())),
+            Uniform::Int3(..) => gl_call!(gl::Uniform3iv(location, count, ints().as_ptr())),
+            Uniform::Int4(..) => gl_call!(gl::Uniform4iv(location, count, ints().as_ptr())),
+            Uniform::Uint(_) => gl_call!(gl::Uniform1uiv(location, count, uints().as_ptr())),
+            Uniform::Uint2(..) => gl_call!(gl::Uniform2uiv(location, count, uints().as_ptr())),
+            Uniform::Uint3(..) => gl_call!(gl::Uniform3uiv(location, count, uints().as_ptr())),
+            Uniform::Uint4(..) => gl_call!(gl::Uniform4uiv(location, count, uints().as_ptr())),
+            Uniform::Mat2(_) => gl_call!(gl::UniformMatrix2fv(location, count, gl::TRUE, floats().as_ptr())),
+            Uniform::Mat3(_) => gl_call!(gl::UniformMatrix3fv(location, count, gl::TRUE, floats().as_ptr())),
+            Uniform::Mat4(_) => gl_call!(gl::UniformMatrix4fv(location, count, gl::TRUE, floats().as_ptr())),
+            Uniform::Texture(_) | Uniform::Array(_) => crash!("Texture arrays and nested arrays are not supported as uniforms."),
+        }
+    }
+}
@@ -5,14 +5,25 @@ use super::super::gl_call;
 
 const F32_SIZE: usize = std::mem::size_of::<f32>();
 pub fn set_vertex_attribs(counts: &[usize]) {
+    set_attribs(counts, 0, 0);
+}
+
+/// Sets per-instance attribs, starting at the `first` location.
+pub fn set_instance_attribs(counts: &[usize], first: u32) {
+    set_attribs(counts, first, 1);
+}
+
+fn set_attribs(counts: &[usize], first: u32, divisor: u32) {
     let stride: i32 = (counts.iter().sum::<usize>() * F32_SIZE) as i32;
     assert_expr!(stride != 0, "Stride must be greater than 0");
 
     let mut offset: i32 = 0;
 
     for (i, c) in counts.iter().enumerate() {
-        gl_call!(gl::VertexAttribPointer(i as u32, *c as i32, gl::FLOAT, gl::FALSE, stride, offset as *const std::ffi::c_void));
-        gl_call!(gl::EnableVertexAttribArray(i as u32));
+        let location = first + i as u32;
+        gl_call!(gl::VertexAttribPointer(location, *c as i32, gl::FLOAT, gl::FALSE, stride, offset as *const std::ffi::c_void));
+        gl_call!(gl::EnableVertexAttribArray(location));
+        gl_call!(gl::VertexAttribDivisor(location, divisor));
 
         offset += (c * F32_SIZE) as i32;
     }
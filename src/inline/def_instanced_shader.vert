@@ -0,0 +1,21 @@
+#version 330 core
+
+layout (location = 0) in vec2 v_Corner;
+layout (location = 1) in vec2 i_Origin;
+layout (location = 2) in vec2 i_AxisX;
+layout (location = 3) in vec2 i_AxisY;
+layout (location = 4) in vec2 i_UV0;
+layout (location = 5) in vec2 i_UV1;
+layout (location = 6) in vec4 i_Col;
+
+out vec4 f_Col;
+out vec2 f_UV;
+
+uniform mat3 mvm;
+
+void main() {
+    vec2 pos = i_Origin + i_AxisX * v_Corner.x + i_AxisY * v_Corner.y;
+    gl_Position = vec4(mvm * vec3(pos, 1.0), 1.0);
+    f_Col = i_Col;
+    f_UV = mix(i_UV0, i_UV1, v_Corner);
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+#version 330 core
+
+layout (location = 0) out vec4 o_Col;
+
+in vec4 f_Col;
+in vec2 f_UV;
+flat in int f_Slot;
+
+uniform sampler2D textures[16];
+uniform float alpha_cutoff;
+
+vec4 sample_slot(int slot, vec2 uv) {
+    switch (slot) {
+        case 0: return texture(textures[0], uv);
+        case 1: return texture(textures[1], uv);
+        case 2: return texture(textures[2], uv);
+        case 3: return texture(textures[3], uv);
+        case 4: return texture(textures[4], uv);
+        case 5: return texture(textures[5], uv);
+        case 6: return texture(textures[6], uv);
+        case 7: return texture(textures[7], uv);
+        case 8: return texture(textures[8], uv);
+        case 9: return texture(textures[9], uv);
+        case 10: return texture(textures[10], uv);
+        case 11: return texture(textures[11], uv);
+        case 12: return texture(textures[12], uv);
+        case 13: return texture(textures[13], uv);
+        case 14: return texture(textures[14], uv);
+        case 15: return texture(textures[15], uv);
+    }
+    return vec4(0.0);
+}
+
+void main() {
+    o_Col = sample_slot(f_Slot, f_UV) * f_Col;
+    if (o_Col.a < alpha_cutoff) {
+        discard;
+    }
+}
\ No newline at end of file
@@ -6,7 +6,11 @@ in vec4 f_Col;
 in vec2 f_UV;
 
 uniform sampler2D main_tex;
+uniform float alpha_cutoff;
 
 void main() {
     o_Col = texture(main_tex, f_UV) * f_Col;
+    if (o_Col.a < alpha_cutoff) {
+        discard;
+    }
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+#version 420 core
+
+#include "nogine/blit.glsl"
+
+uniform float exposure;
+// 0: Reinhard, 1: ACES
+uniform int tonemap_op;
+
+vec3 aces(vec3 x) {
+    return clamp((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14), 0.0, 1.0);
+}
+
+void main() {
+    vec4 col = texture(screen_tex, f_Uv);
+    vec3 hdr = col.rgb * exposure;
+
+    vec3 ldr = tonemap_op == 0 ? hdr / (hdr + 1.0) : aces(hdr);
+    o_Col = vec4(ldr, col.a * f_Alpha);
+}
\ No newline at end of file
@@ -0,0 +1,8 @@
+#pragma once
+
+layout (location = 0) out vec4 o_Col;
+
+in vec2 f_Uv;
+in float f_Alpha;
+
+layout (binding = 0) uniform sampler2D screen_tex;
\ No newline at end of file
@@ -0,0 +1,13 @@
+#pragma once
+
+vec3 srgb_to_linear(vec3 col) {
+    return mix(col / 12.92, pow((col + 0.055) / 1.055, vec3(2.4)), step(0.04045, col));
+}
+
+vec3 linear_to_srgb(vec3 col) {
+    return mix(col * 12.92, 1.055 * pow(col, vec3(1.0 / 2.4)) - 0.055, step(0.0031308, col));
+}
+
+float luminance(vec3 col) {
+    return dot(col, vec3(0.2126, 0.7152, 0.0722));
+}
\ No newline at end of file
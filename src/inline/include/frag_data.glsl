@@ -0,0 +1,8 @@
+#pragma once
+
+// Define NOGINE_FRAG_DATA_COUNT before including to match the number of color attachments.
+#ifndef NOGINE_FRAG_DATA_COUNT
+#define NOGINE_FRAG_DATA_COUNT 4
+#endif
+
+layout (location = 0) out vec4 o_FragData[NOGINE_FRAG_DATA_COUNT];
\ No newline at end of file
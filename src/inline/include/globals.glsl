@@ -0,0 +1,8 @@
+#pragma once
+
+layout (std140) uniform Globals {
+    mat3 g_Cam;
+    vec2 g_Resolution;
+    float g_Time;
+    float g_DeltaTime;
+};
\ No newline at end of file
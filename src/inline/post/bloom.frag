@@ -0,0 +1,11 @@
+#version 420 core
+
+#include "nogine/blit.glsl"
+
+// Scale of the glow added on top of the image, can go over 1
+uniform float intensity;
+
+void main() {
+    vec4 col = texture(screen_tex, f_Uv);
+    o_Col = vec4(col.rgb * intensity, col.a * f_Alpha);
+}
\ No newline at end of file
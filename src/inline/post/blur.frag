@@ -0,0 +1,33 @@
+#version 420 core
+
+#include "nogine/blit.glsl"
+
+// Direction of the pass, (1, 0) or (0, 1)
+uniform vec2 direction;
+// Standard deviation in pixels
+uniform float sigma;
+
+float weight(int i) {
+    return exp(-float(i * i) / (2.0 * sigma * sigma));
+}
+
+void main() {
+    vec2 texel = direction / vec2(textureSize(screen_tex, 0));
+    int radius = int(ceil(sigma * 3.0));
+
+    vec4 col = texture(screen_tex, f_Uv);
+    float total = 1.0;
+
+    // Each pair of texels is fetched with a single linear sample placed between them by their weights
+    for (int i = 1; i <= radius; i += 2) {
+        float w0 = weight(i);
+        float w1 = i + 1 <= radius ? weight(i + 1) : 0.0;
+        float w = w0 + w1;
+        float offset = (float(i) * w0 + float(i + 1) * w1) / w;
+
+        col += (texture(screen_tex, f_Uv + texel * offset) + texture(screen_tex, f_Uv - texel * offset)) * w;
+        total += 2.0 * w;
+    }
+
+    o_Col = col / total * vec4(1.0, 1.0, 1.0, f_Alpha);
+}
\ No newline at end of file
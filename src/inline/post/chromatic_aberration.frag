@@ -0,0 +1,16 @@
+#version 420 core
+
+#include "nogine/blit.glsl"
+
+// Offset in pixels at the corners
+uniform float offset;
+
+void main() {
+    vec2 dir = (f_Uv - 0.5) * 2.0 * offset / vec2(textureSize(screen_tex, 0));
+
+    vec4 col = texture(screen_tex, f_Uv);
+    float r = texture(screen_tex, f_Uv + dir).r;
+    float b = texture(screen_tex, f_Uv - dir).b;
+
+    o_Col = vec4(r, col.g, b, col.a * f_Alpha);
+}
\ No newline at end of file
@@ -0,0 +1,24 @@
+#version 420 core
+
+#include "nogine/blit.glsl"
+
+uniform float curvature;
+uniform float scanline_intensity;
+uniform float scanline_count;
+
+void main() {
+    vec2 uv = f_Uv * 2.0 - 1.0;
+    uv += uv * (uv.yx * uv.yx) * curvature;
+    uv = uv * 0.5 + 0.5;
+
+    if (uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+        o_Col = vec4(0.0, 0.0, 0.0, f_Alpha);
+        return;
+    }
+
+    vec4 col = texture(screen_tex, uv);
+    float scanline = sin(uv.y * scanline_count * 3.14159265) * 0.5 + 0.5;
+    col.rgb *= mix(1.0, scanline, scanline_intensity);
+
+    o_Col = col * vec4(1.0, 1.0, 1.0, f_Alpha);
+}
\ No newline at end of file
@@ -0,0 +1,20 @@
+#version 420 core
+
+#include "nogine/blit.glsl"
+
+// Number of values per channel
+uniform float levels;
+// Size of each cell of the pattern in pixels
+uniform float dither_scale;
+
+const float BAYER[16] = float[](0.0, 8.0, 2.0, 10.0, 12.0, 4.0, 14.0, 6.0, 3.0, 11.0, 1.0, 9.0, 15.0, 7.0, 13.0, 5.0);
+
+void main() {
+    vec4 col = texture(screen_tex, f_Uv);
+
+    ivec2 cell = ivec2(gl_FragCoord.xy / dither_scale) % 4;
+    float t = (BAYER[cell.y * 4 + cell.x] + 0.5) / 16.0;
+    vec3 quantized = floor(col.rgb * (levels - 1.0) + t) / (levels - 1.0);
+
+    o_Col = vec4(clamp(quantized, 0.0, 1.0), col.a * f_Alpha);
+}
\ No newline at end of file
@@ -0,0 +1,30 @@
+#version 420 core
+
+#include "nogine/blit.glsl"
+
+// Slices of the blue axis laid out horizontally, red along x and green along y
+uniform sampler2D lut_tex;
+uniform float lut_size;
+uniform float intensity;
+
+vec3 sample_lut(vec3 col) {
+    col = clamp(col, 0.0, 1.0);
+
+    float blue = col.b * (lut_size - 1.0);
+    float b0 = floor(blue);
+    float b1 = min(b0 + 1.0, lut_size - 1.0);
+
+    vec2 px = col.rg * (lut_size - 1.0) + 0.5;
+    vec2 size = vec2(lut_size * lut_size, lut_size);
+
+    vec3 s0 = texture(lut_tex, (px + vec2(b0 * lut_size, 0.0)) / size).rgb;
+    vec3 s1 = texture(lut_tex, (px + vec2(b1 * lut_size, 0.0)) / size).rgb;
+    return mix(s0, s1, blue - b0);
+}
+
+void main() {
+    vec4 col = texture(screen_tex, f_Uv);
+    vec3 graded = sample_lut(col.rgb);
+
+    o_Col = vec4(mix(col.rgb, graded, intensity), col.a * f_Alpha);
+}
\ No newline at end of file
@@ -0,0 +1,13 @@
+#version 420 core
+
+#include "nogine/blit.glsl"
+
+// Size of each block in pixels
+uniform float pixel_size;
+
+void main() {
+    vec2 res = vec2(textureSize(screen_tex, 0));
+    vec2 uv = (floor(f_Uv * res / pixel_size) + 0.5) * pixel_size / res;
+
+    o_Col = texture(screen_tex, uv) * vec4(1.0, 1.0, 1.0, f_Alpha);
+}
\ No newline at end of file
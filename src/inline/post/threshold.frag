@@ -0,0 +1,14 @@
+#version 420 core
+
+#include "nogine/blit.glsl"
+#include "nogine/color.glsl"
+
+uniform float threshold;
+
+void main() {
+    vec4 col = texture(screen_tex, f_Uv);
+    float l = luminance(col.rgb);
+    float factor = max(l - threshold, 0.0) / max(l, 0.0001);
+
+    o_Col = vec4(col.rgb * factor, 1.0);
+}
\ No newline at end of file
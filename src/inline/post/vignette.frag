@@ -0,0 +1,17 @@
+#version 420 core
+
+#include "nogine/blit.glsl"
+
+uniform vec4 vignette_col;
+uniform float intensity;
+// Distance from the center where the vignette starts, 1 is the corners
+uniform float radius;
+uniform float softness;
+
+void main() {
+    vec4 col = texture(screen_tex, f_Uv);
+    float dist = distance(f_Uv, vec2(0.5)) * 1.41421356;
+    float v = smoothstep(radius, radius + softness, dist) * intensity * vignette_col.a;
+
+    o_Col = vec4(mix(col.rgb, vignette_col.rgb, v), col.a * f_Alpha);
+}
\ No newline at end of file
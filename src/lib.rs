@@ -11,6 +11,7 @@ pub mod utils {
     pub mod heap;
     pub mod rng;
     pub mod timer;
+    pub mod golden;
     pub(crate) mod ptr_slice;
 }
 pub mod resource;
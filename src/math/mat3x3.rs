@@ -59,6 +59,16 @@ impl mat3 {
         return mat;
     }
 
+    /// Camera matrix rotated by `rot` radians around `pos`.
+    pub fn cam_matrix_ext(pos: vec2, dims: vec2, rot: f32) -> Self {
+        let (s, c) = rot.sin_cos();
+
+        let mut mat = Self::IDENTITY.clone();
+        mat.rows[0] = [c / dims.0, -s / dims.0, (-c * pos.0 - s * pos.1) / dims.0];
+        mat.rows[1] = [-s / dims.1, -c / dims.1, (s * pos.0 - c * pos.1) / dims.1];
+        return mat;
+    }
+
     pub fn determinant(&self) -> f32 {
         return self.rows[0][0] * (self.rows[1][1] * self.rows[2][2] - self.rows[2][1] * self.rows[1][2]) -
             self.rows[0][1] * (self.rows[1][0] * self.rows[2][2] - self.rows[2][0] * self.rows[1][2]) +
@@ -97,6 +107,10 @@ impl mat3 {
         return res;
     }
 
+    pub fn rows(&self) -> &[[f32; 3]; 3] {
+        return &self.rows;
+    }
+
     pub fn ptr(&self) -> *const f32 {
         return self.rows.as_ptr() as *const f32;
     }
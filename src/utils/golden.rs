@@ -0,0 +1,199 @@
+use std::path::{Path, PathBuf};
+
+use image::ImageError;
+use thiserror::Error;
+
+use crate::{graphics::{render_scope::RenderScope, texture::{Pixels, TextureError, TextureFiltering}, Graphics}, log_info, math::uvec2, Res};
+
+/// Environment variable that, when set, writes the rendered images as the references, overwriting them if they exist.
+pub const BLESS_ENV_VAR: &str = "NOGINE_BLESS_GOLDEN";
+
+#[derive(Debug, Error)]
+pub enum GoldenError {
+    #[error("{0}")]
+    TextureError(#[from] TextureError),
+    #[error("{0}")]
+    ImageError(#[from] ImageError),
+    #[error("Reference '{}' not found. Set `NOGINE_BLESS_GOLDEN` to write it", path.display())]
+    MissingReference { path: PathBuf },
+    #[error("Resolution mismatch (Expected ({}, {}), got ({}, {}))", expected.0, expected.1, actual.0, actual.1)]
+    ResolutionMismatch { expected: uvec2, actual: uvec2 },
+    #[error("Image mismatch: {mismatched} out of {total} pixels differ. Actual image written to '{}', diff written to '{}'", actual_path.display(), diff_path.display())]
+    Mismatch { mismatched: usize, total: usize, actual_path: PathBuf, diff_path: PathBuf },
+}
+
+/// Bundles the config for a golden image comparison.
+#[derive(Debug, Clone)]
+pub struct GoldenCfg {
+    /// Resolution the scene is rendered at.
+    pub res: uvec2,
+    /// Perceptual difference, in the `[0, 1]` range, above which a pixel is considered different.
+    pub threshold: f32,
+    /// Ratio of pixels, in the `[0, 1]` range, that are allowed to differ.
+    pub tolerance: f32,
+    /// Directory where the actual and diff images are written on mismatch.
+    pub output_dir: PathBuf,
+}
+
+impl Default for GoldenCfg {
+    fn default() -> Self {
+        Self { res: uvec2(256, 256), threshold: 0.1, tolerance: 0.0, output_dir: PathBuf::from("target/golden") }
+    }
+}
+
+/// Result of comparing two images.
+#[derive(Debug, Clone)]
+pub struct DiffReport {
+    pub mismatched: usize,
+    pub total: usize,
+    pub diff: Box<[u8]>,
+}
+
+impl DiffReport {
+    /// Returns the ratio of mismatched pixels.
+    pub fn ratio(&self) -> f32 {
+        return self.mismatched as f32 / self.total.max(1) as f32;
+    }
+}
+
+/// Renders `render_fn` through a new `RenderScope` and reads the result back.
+/// - A window (can be headless) must have been initialized.
+pub fn render<F: FnMut()>(res: uvec2, render_fn: F) -> Pixels<'static> {
+    let mut scope = RenderScope::new();
+    Graphics::with_scope(&mut scope, render_fn);
+
+    let (tex, _) = scope.render_to_texture(res, TextureFiltering::Closest, None);
+    return tex.download();
+}
+
+/// Renders `render_fn` and compares the result against the png at `reference`.<br>
+/// - If `NOGINE_BLESS_GOLDEN` is set, the reference is (re)written instead.
+/// - A missing reference is an error, so a test can't pass without comparing anything.
+/// - On mismatch, `<name>.actual.png` and `<name>.diff.png` are written into `cfg.output_dir`.
+pub fn assert_golden<F: FnMut()>(reference: impl AsRef<Path>, cfg: &GoldenCfg, render_fn: F) -> Res<(), GoldenError> {
+    let reference = reference.as_ref();
+    let actual = render(cfg.res, render_fn);
+
+    if std::env::var_os(BLESS_ENV_VAR).is_some() {
+        if let Some(parent) = reference.parent() {
+            std::fs::create_dir_all(parent).map_err(TextureError::from)?;
+        }
+        actual.save_png(reference)?;
+
+        log_info!("Golden image '{}' written.", reference.display());
+        return Ok(());
+    }
+
+    if !reference.exists() {
+        return Err(GoldenError::MissingReference { path: reference.into() });
+    }
+
+    let expected = load_png(reference)?;
+    if expected.res() != actual.res() {
+        return Err(GoldenError::ResolutionMismatch { expected: expected.res(), actual: actual.res() });
+    }
+
+    let report = compare(&expected, &actual, cfg.threshold);
+    if report.ratio() <= cfg.tolerance {
+        return Ok(());
+    }
+
+    let name = reference.file_stem().map(|x| x.to_string_lossy().into_owned()).unwrap_or_else(|| "golden".into());
+    std::fs::create_dir_all(&cfg.output_dir).map_err(TextureError::from)?;
+
+    let actual_path = cfg.output_dir.join(format!("{name}.actual.png"));
+    let diff_path = cfg.output_dir.join(format!("{name}.diff.png"));
+    actual.save_png(&actual_path)?;
+    Pixels::owned(report.diff, actual.res()).save_png(&diff_path)?;
+
+    return Err(GoldenError::Mismatch { mismatched: report.mismatched, total: report.total, actual_path, diff_path });
+}
+
+/// Compares two images of the same resolution using a perceptual color difference.<br>
+/// - `threshold` is in the `[0, 1]` range, pixels with a greater difference count as mismatched.
+/// - Mismatched pixels are painted red in the diff image, the rest are a faded version of `actual`.
+pub fn compare(expected: &Pixels<'_>, actual: &Pixels<'_>, threshold: f32) -> DiffReport {
+    assert_eq!(expected.res(), actual.res(), "Both images must have the same resolution.");
+
+    let mut mismatched = 0;
+    let mut diff = Vec::with_capacity(actual.as_bytes().len());
+    for (e, a) in expected.as_bytes().chunks_exact(4).zip(actual.as_bytes().chunks_exact(4)) {
+        // The delta is squared, like in pixelmatch
+        if internal::color_delta(e, a) > threshold * threshold {
+            mismatched += 1;
+            diff.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let luma = internal::blend_white(internal::luma(a), a[3]);
+            let faded = (255.0 - (255.0 - luma) * 0.1) as u8;
+            diff.extend_from_slice(&[faded, faded, faded, 255]);
+        }
+    }
+
+    return DiffReport { mismatched, total: (actual.res().0 * actual.res().1) as usize, diff: diff.into_boxed_slice() };
+}
+
+fn load_png(path: &Path) -> Res<Pixels<'static>, GoldenError> {
+    let img = image::open(path)?.to_rgba8();
+    let res = uvec2(img.width(), img.height());
+    return Ok(Pixels::owned(img.into_raw().into_boxed_slice(), res));
+}
+
+mod internal {
+    // Based on the YIQ color difference used by pixelmatch.
+    // https://github.com/mapbox/pixelmatch
+    const MAX_DELTA: f32 = 35215.0;
+
+    pub fn color_delta(a: &[u8], b: &[u8]) -> f32 {
+        if a == b {
+            return 0.0;
+        }
+
+        let (ar, ag, ab) = (blend_white(a[0] as f32, a[3]), blend_white(a[1] as f32, a[3]), blend_white(a[2] as f32, a[3]));
+        let (br, bg, bb) = (blend_white(b[0] as f32, b[3]), blend_white(b[1] as f32, b[3]), blend_white(b[2] as f32, b[3]));
+
+        let y = rgb_to_y(ar, ag, ab) - rgb_to_y(br, bg, bb);
+        let i = rgb_to_i(ar, ag, ab) - rgb_to_i(br, bg, bb);
+        let q = rgb_to_q(ar, ag, ab) - rgb_to_q(br, bg, bb);
+
+        return (0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q) / MAX_DELTA;
+    }
+
+    pub fn luma(px: &[u8]) -> f32 {
+        return rgb_to_y(px[0] as f32, px[1] as f32, px[2] as f32);
+    }
+
+    pub fn blend_white(c: f32, alpha: u8) -> f32 {
+        return 255.0 + (c - 255.0) * (alpha as f32 / 255.0);
+    }
+
+    fn rgb_to_y(r: f32, g: f32, b: f32) -> f32 { r * 0.29889531 + g * 0.58662247 + b * 0.11448223 }
+    fn rgb_to_i(r: f32, g: f32, b: f32) -> f32 { r * 0.59597799 - g * 0.27417610 - b * 0.32180189 }
+    fn rgb_to_q(r: f32, g: f32, b: f32) -> f32 { r * 0.21147017 - g * 0.52261711 + b * 0.31114694 }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{graphics::texture::Pixels, math::uvec2};
+
+    use super::compare;
+
+    #[test]
+    fn identical() {
+        let a = Pixels::owned(vec![10, 20, 30, 255, 200, 100, 50, 255].into_boxed_slice(), uvec2(2, 1));
+        let b = Pixels::owned(vec![10, 20, 30, 255, 200, 100, 50, 255].into_boxed_slice(), uvec2(2, 1));
+
+        let report = compare(&a, &b, 0.0);
+        assert_eq!(report.mismatched, 0);
+        assert_eq!(report.total, 2);
+    }
+
+    #[test]
+    fn threshold() {
+        let a = Pixels::owned(vec![0, 0, 0, 255, 0, 0, 0, 255].into_boxed_slice(), uvec2(2, 1));
+        let b = Pixels::owned(vec![1, 1, 1, 255, 255, 255, 255, 255].into_boxed_slice(), uvec2(2, 1));
+
+        let report = compare(&a, &b, 0.1);
+        assert_eq!(report.mismatched, 1);
+        assert_eq!(&report.diff[4..8], &[255, 0, 0, 255]);
+    }
+}
@@ -3,7 +3,7 @@ use std::{sync::mpsc::Receiver, time::Instant};
 use glfw::Context as GlfwContext;
 use thiserror::Error;
 
-use crate::{audio::Audio, graphics::{pipeline::{DefaultRenderPipeline, RenderPipeline}, Graphics, RenderStats}, input::Input, log_info, log_warn, logging::Logger, math::uvec2, unwrap_opt, Res};
+use crate::{audio::Audio, graphics::{pipeline::{DefaultRenderPipeline, RenderPipeline, RenderTexture}, texture::TextureFiltering, Graphics, RenderStats}, input::Input, log_info, log_warn, logging::Logger, math::uvec2, unwrap_opt, Res};
 
 use super::gl_call;
 
@@ -25,6 +25,7 @@ pub struct WindowCfg<'a> {
     pub title: &'a str,
     pub mode: WindowMode,
     pub main: bool,
+    pub headless: bool,
 }
 
 impl<'a> WindowCfg<'a> {
@@ -43,6 +44,15 @@ impl<'a> WindowCfg<'a> {
         return self;
     }
 
+    /// Enables headless mode.<br>
+    /// - The window is created hidden, and all rendering is done into a `RenderTexture` instead of the screen.
+    /// - Buffers are never swapped and window events are not polled.
+    /// - On machines without a display, a virtual display and a software GL driver are still required to create the context.
+    pub fn headless(mut self, val: bool) -> Self {
+        self.headless = val;
+        return self;
+    }
+
     pub fn init(self) -> Res<Window, WindowError> {
         Logger::init();
         
@@ -50,12 +60,16 @@ impl<'a> WindowCfg<'a> {
         
         let monitor = glfw::Monitor::from_primary();
         let mode = match self.mode {
-            WindowMode::Fullscreen => glfw::WindowMode::FullScreen(&monitor),
-            WindowMode::Windowed => glfw::WindowMode::Windowed,
+            WindowMode::Fullscreen if !self.headless => glfw::WindowMode::FullScreen(&monitor),
+            _ => glfw::WindowMode::Windowed,
         };
 
+        if self.headless {
+            glfw.window_hint(glfw::WindowHint::Visible(false));
+        }
+
         let (mut window, events) = glfw.create_window(self.res.0, self.res.1, self.title, mode).ok_or(WindowError::CreationFailure)?;
-        window.set_all_polling(true);
+        window.set_all_polling(!self.headless);
         window.make_current();
 
         gl::load_with(|x| window.get_proc_address(x) as *const _);
@@ -64,14 +78,16 @@ impl<'a> WindowCfg<'a> {
         Graphics::init();
         Audio::init();
 
+        let headless_rt = if self.headless { Some(RenderTexture::new(self.res, TextureFiltering::Closest)) } else { None };
+
         log_info!("Window initialized.");
-        return Ok(Window { window, events, glfw, def_res: self.res, last_frame: Instant::now(), target_framerate: None, ts: 0.02 });
+        return Ok(Window { window, events, glfw, def_res: self.res, headless_rt, last_frame: Instant::now(), target_framerate: None, ts: 0.02 });
     }
 }
 
 impl<'a> Default for WindowCfg<'a> {
     fn default() -> Self {
-        Self { res: uvec2(1280, 720), title: "Nogine Window", mode: WindowMode::Windowed, main: false }
+        Self { res: uvec2(1280, 720), title: "Nogine Window", mode: WindowMode::Windowed, main: false, headless: false }
     }
 }
 
@@ -84,6 +100,7 @@ pub struct Window {
     events: Receiver<(f64, glfw::WindowEvent)>,
     glfw: glfw::Glfw,
     def_res: uvec2,
+    headless_rt: Option<RenderTexture>,
 
     last_frame: Instant,
     target_framerate: Option<f32>,
@@ -97,6 +114,10 @@ impl Window {
     }
 
     fn handle_events(&mut self) {
+        if self.is_headless() {
+            return;
+        }
+
         self.glfw.poll_events();
 
         for (_, ev) in glfw::flush_messages(&self.events) {
@@ -108,6 +129,13 @@ impl Window {
     pub fn pre_tick(&mut self, pipeline: Option<&dyn RenderPipeline>) -> RenderStats {
         let mut def_pipeline = DefaultRenderPipeline;
         let pipeline = pipeline.unwrap_or(&mut def_pipeline);
+
+        let size = self.get_size();
+        if let Some(rt) = &self.headless_rt {
+            if rt.res() != size {
+                self.headless_rt = Some(RenderTexture::new(size, TextureFiltering::Closest));
+            }
+        }
         
         let stats = Graphics::render(pipeline, self.get_size(), self);
         Audio::tick();
@@ -215,9 +243,30 @@ impl Window {
 
     #[inline]
     pub(crate) fn swap_buffers(&mut self) {
+        if self.is_headless() {
+            return;
+        }
+
         self.window.swap_buffers();
     }
 
+    /// Returns if the window was created in headless mode.
+    #[inline]
+    pub fn is_headless(&self) -> bool {
+        self.headless_rt.is_some()
+    }
+
+    /// Returns the render texture the frames are rendered into when in headless mode.
+    #[inline]
+    pub fn headless_target(&self) -> Option<&RenderTexture> {
+        self.headless_rt.as_ref()
+    }
+
+    #[inline]
+    pub(crate) fn headless_target_mut(&mut self) -> Option<&mut RenderTexture> {
+        self.headless_rt.as_mut()
+    }
+
     /*fn force_framerate(&self, target_framerate: f32) {
         assert_expr!(target_framerate > 0.0, "Target framerate must be greater than 0");
         
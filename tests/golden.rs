@@ -0,0 +1,85 @@
+use nogine::{color::{Color, Color4}, graphics::{texture::{SpriteAtlas, Texture, TextureCfg, TextureFiltering, TextureWrapping}, ui::text::{font::{BitmapFont, FontCfg}, HorTextAlignment, VerTextAlignment}, BlendFactor, BlendOp, BlendingMode, Graphics}, math::{uvec2, vec2}, utils::golden::{assert_golden, GoldenCfg}, window::WindowCfg};
+
+const REFERENCE_DIR: &str = "tests/golden";
+const FONT_DATA: &[u8] = include_bytes!("../examples/res/text.png");
+
+// Every scene is rendered from a single test, as the window has to live on one thread.
+// Needs a GL context, run it with `cargo test --test golden -- --ignored`.
+// Set `NOGINE_BLESS_GOLDEN` to write the references after an intended change, and commit them.
+#[test]
+#[ignore = "needs a GL context"]
+fn golden_examples() {
+    let _window = WindowCfg::default().res((256, 144)).title("Golden Tests").headless(true).init().unwrap_or_else(|e| panic!("{e}"));
+    let cfg = GoldenCfg { res: uvec2(256, 144), ..Default::default() };
+
+    assert_golden(format!("{REFERENCE_DIR}/figures.png"), &cfg, || {
+        Graphics::set_cam(vec2::ZERO, vec2(1.5 * 16.0 / 9.0, 1.5));
+
+        Graphics::draw_rect(vec2(-1.55, -0.5), vec2::ONE, Color4::CYAN);
+        Graphics::draw_circle(vec2::ZERO, 0.5, Color4::YELLOW);
+        Graphics::draw_polygon(vec2(1.0, 0.0), 0.5, 0.0, 6, Color::PINK);
+    }).unwrap_or_else(|e| panic!("{e}"));
+
+    assert_golden(format!("{REFERENCE_DIR}/pivots.png"), &cfg, || {
+        Graphics::set_cam(vec2::ZERO, vec2(2.0 * 16.0 / 9.0, 2.0));
+
+        Graphics::set_pivot(vec2::one(0.5));
+        Graphics::draw_rect_full(vec2::ZERO, vec2::ONE, 0.5, [Color4::RED; 4]);
+        Graphics::set_pivot(vec2::ZERO);
+    }).unwrap_or_else(|e| panic!("{e}"));
+
+    assert_golden(format!("{REFERENCE_DIR}/blending_modes.png"), &cfg, || {
+        Graphics::set_clear_col(Color4(0.1, 0.2, 0.3, 1.0));
+        Graphics::set_cam(vec2::ZERO, vec2(1.5 * 16.0 / 9.0, 1.5));
+
+        Graphics::draw_rect(vec2(-2.75, -0.25), vec2(5.5, 0.5), Color4::GRAY);
+        Graphics::draw_rect(vec2(-2.5, 0.25), vec2(1.0, 1.0), Color4::RED);
+
+        let modes = [
+            (BlendingMode::Additive, vec2(-1.25, 0.25), Color4::GREEN),
+            (BlendingMode::Multiplicative, vec2(0.0, 0.25), Color4::BLUE),
+            (BlendingMode::Screen, vec2(1.25, 0.25), Color4::RED),
+            (BlendingMode::Subtract, vec2(-2.5, -1.25), Color4::GREEN),
+            (BlendingMode::Max, vec2(-1.25, -1.25), Color4::BLUE),
+            (BlendingMode::Overlay, vec2(0.0, -1.25), Color4::YELLOW),
+            (BlendingMode::Custom {
+                src: BlendFactor::OneMinusDstColor, dst: BlendFactor::Zero, op: BlendOp::Add,
+                alpha_src: BlendFactor::Zero, alpha_dst: BlendFactor::One, alpha_op: BlendOp::Add
+            }, vec2(1.25, -1.25), Color4::WHITE),
+        ];
+
+        for (mode, pos, color) in modes {
+            Graphics::set_blending_mode(mode);
+            Graphics::draw_rect(pos, vec2(1.0, 1.0), color);
+        }
+
+        Graphics::set_blending_mode(BlendingMode::AlphaMix);
+    }).unwrap_or_else(|e| panic!("{e}"));
+
+    let font = BitmapFont::new(
+        SpriteAtlas::new(
+            Texture::load(std::io::Cursor::new(FONT_DATA), TextureCfg { filtering: TextureFiltering::Closest, wrapping: TextureWrapping::Clamp }).unwrap_or_else(|e| panic!("{e}")),
+            uvec2(6, 8)
+        ),
+        "abcdefghijklmnopqrstuvwxyz",
+        FontCfg { monospace: true, char_spacing: 2.0 / 6.0, line_spacing: 4.0 / 8.0, ..Default::default() }
+    );
+
+    assert_golden(format!("{REFERENCE_DIR}/text_alignment.png"), &cfg, || {
+        Graphics::set_cam(vec2::ZERO, vec2(1.5 * 16.0 / 9.0, 1.5));
+        Graphics::set_pivot(vec2::one(0.5));
+
+        let alignments = [
+            (vec2(0.0, 0.9), HorTextAlignment::Left, VerTextAlignment::Top),
+            (vec2(0.0, 0.0), HorTextAlignment::Center, VerTextAlignment::Middle),
+            (vec2(0.0, -0.9), HorTextAlignment::Right, VerTextAlignment::Bottom),
+        ];
+
+        for (pos, hor, ver) in alignments {
+            let (quad, _) = Graphics::text(pos, vec2(2.0, 0.5), 0.0, "aligned text").font_size(0.1).font(&font).hor_align(hor).ver_align(ver).draw();
+            Graphics::draw_debug_quad(quad, Color4::LIME);
+        }
+
+        Graphics::set_pivot(vec2::ZERO);
+    }).unwrap_or_else(|e| panic!("{e}"));
+}
\ No newline at end of file